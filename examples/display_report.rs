@@ -0,0 +1,29 @@
+use std::fmt;
+
+use tabprinter::{Alignment, Table, TableStyle};
+
+struct Report {
+    title: String,
+    table: Table,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.title)?;
+        self.table.fmt_indented(f, "  ")
+    }
+}
+
+fn main() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Service", 10, Alignment::Left);
+    table.add_column("Uptime", 8, Alignment::Right);
+    table.add_row(vec!["api".to_string(), "99.98%".to_string()]);
+    table.add_row(vec!["worker".to_string(), "99.91%".to_string()]);
+
+    let report = Report {
+        title: "Weekly availability".to_string(),
+        table,
+    };
+    print!("{}", report);
+}
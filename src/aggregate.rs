@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/aggregate.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::{ColumnRef, Format, Table, TableError};
+
+/// Footer text for a column whose aggregate fails, e.g. on a cell that is
+/// not a number.
+pub const FOOTER_ERROR: &str = "#ERR";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Count,
+}
+
+impl Table {
+    /// Aggregates the numeric values of `column`. Blank cells are skipped and
+    /// thousands separators (`,`, `_`, spaces) are ignored; `Count` counts the
+    /// non-blank cells without parsing them. Numbers are read with the
+    /// column's locale.
+    pub fn aggregate(
+        &self,
+        column: impl Into<ColumnRef>,
+        aggregate: Aggregate,
+    ) -> Result<f64, TableError> {
+        let column = self.column_index(column)?;
+        let cells = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| (i, row[column].trim()))
+            .filter(|(_, cell)| !cell.is_empty());
+        if aggregate == Aggregate::Count {
+            return Ok(cells.count() as f64);
+        }
+
+        let locale = self.locale_of(column);
+        let values = cells
+            .map(|(row, cell)| {
+                locale.parse(cell).ok_or_else(|| TableError::NotNumeric {
+                    row,
+                    column,
+                    value: cell.to_string(),
+                })
+            })
+            .collect::<Result<Vec<f64>, _>>()?;
+        if values.is_empty() && aggregate != Aggregate::Sum {
+            return Err(TableError::NoValues { column });
+        }
+        Ok(match aggregate {
+            Aggregate::Sum => values.iter().sum(),
+            Aggregate::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Count => unreachable!(),
+        })
+    }
+
+    /// Shows `aggregate` of `column` below the last row, written with as
+    /// many decimals as the column's numbers have (or its `Fixed` format
+    /// asks for); a mean gets up to two more. A column with a cell that is
+    /// not a number shows `FOOTER_ERROR`; `aggregate` returns the error.
+    pub fn set_footer(
+        &mut self,
+        column: impl Into<ColumnRef>,
+        aggregate: Aggregate,
+    ) -> Result<(), TableError> {
+        let column = self.column_index(column)?;
+        self.footers.retain(|(c, _)| *c != column);
+        self.footers.push((column, aggregate));
+        Ok(())
+    }
+
+    pub(crate) fn footer_cell(&self, column: usize) -> Option<String> {
+        let (_, aggregate) = self.footers.iter().find(|(c, _)| *c == column)?;
+        let value = match self.aggregate(column, *aggregate) {
+            Ok(value) => value,
+            Err(TableError::NoValues { .. }) => return Some(String::new()),
+            Err(_) => return Some(FOOTER_ERROR.to_string()),
+        };
+        let precision = self.precision(column);
+        let text = match aggregate {
+            Aggregate::Count => format!("{}", value),
+            Aggregate::Mean => {
+                let text = format!("{:.*}", precision + 2, value);
+                let zeros = text.len() - text.trim_end_matches('0').len();
+                text[..text.len() - zeros.min(2)]
+                    .trim_end_matches('.')
+                    .to_string()
+            }
+            _ => format!("{:.*}", precision, value),
+        };
+        Some(self.locale_of(column).localize(&text))
+    }
+
+    /// Decimals of the column's `Fixed` format, or else the most decimals
+    /// any of its numbers has.
+    fn precision(&self, column: usize) -> usize {
+        if let Format::Fixed { decimals } = self.columns[column].format {
+            return decimals;
+        }
+        let locale = self.locale_of(column);
+        self.rows
+            .iter()
+            .map(|row| row[column].trim())
+            .filter(|cell| locale.parse(cell).is_some())
+            .map(|cell| {
+                cell.rsplit_once(locale.decimal).map_or(0, |(_, fraction)| {
+                    fraction.chars().take_while(char::is_ascii_digit).count()
+                })
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn footer_cells(&self) -> Option<Vec<String>> {
+        if self.footers.is_empty() {
+            return None;
+        }
+        Some(
+            (0..self.columns.len())
+                .map(|i| self.footer_cell(i).unwrap_or_default())
+                .collect(),
+        )
+    }
+
+    /// The footer, then the grand total of a page.
+    pub(crate) fn footer_rows(&self) -> Vec<Vec<String>> {
+        self.footer_cells()
+            .into_iter()
+            .chain(self.grand_total.clone())
+            .collect()
+    }
+}
+
+pub(crate) fn parse_number(cell: &str) -> Option<f64> {
+    let cleaned: String = cell
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ',' | '_') && !c.is_whitespace())
+        .collect();
+    cleaned.parse().ok()
+}
+
+pub(crate) fn format_number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/bidi.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::borrow::Cow;
+use unicode_bidi::{BidiInfo, Level};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Bidi {
+    #[default]
+    Off,
+    /// Reorder right-to-left runs into visual order, for terminals without bidi support.
+    Reorder,
+    /// Wrap right-to-left cells in Unicode isolates, for terminals that apply bidi
+    /// themselves, so text cannot be reordered across column borders.
+    Isolate,
+}
+
+pub(crate) fn apply(text: &str, bidi: Bidi) -> Cow<'_, str> {
+    if bidi == Bidi::Off {
+        return Cow::Borrowed(text);
+    }
+    let info = BidiInfo::new(text, Some(Level::ltr()));
+    if !info.has_rtl() {
+        return Cow::Borrowed(text);
+    }
+    match (bidi, info.paragraphs.first()) {
+        (Bidi::Reorder, Some(paragraph)) => info.reorder_line(paragraph, paragraph.range.clone()),
+        (Bidi::Isolate, _) => Cow::Owned(format!("\u{2068}{}\u{2069}", text)),
+        _ => Cow::Borrowed(text),
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/border.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::sync::Arc;
+
+use crate::{ColorSpec, Table};
+
+/// The line of the table a border segment is drawn on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderKind {
+    Top,
+    /// The rule under the header, also drawn above footers.
+    BelowHeader,
+    Bottom,
+    /// The side borders and separators of the header row.
+    Header,
+    /// Those of a data row, with its index into the table's rows.
+    Row(usize),
+    Footer,
+}
+
+/// Where on its line a segment sits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderPosition {
+    Begin,
+    /// The horizontal rule above or below a column.
+    Rule(usize),
+    /// The separator to the left of a column.
+    Separator(usize),
+    End,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BorderSegment<'a> {
+    pub kind: BorderKind,
+    pub position: BorderPosition,
+    /// What the style draws here, already in the render's charset.
+    pub glyphs: &'a str,
+}
+
+/// Draws border segments differently from the style, e.g. in a color
+/// gradient from top to bottom. Both methods fall back to the style's
+/// drawing when they return `None`.
+pub trait BorderPainter: Send + Sync {
+    /// Color for the segment instead of the theme's border color.
+    fn color(&self, _segment: &BorderSegment) -> Option<ColorSpec> {
+        None
+    }
+
+    /// Glyphs to draw instead of the style's. They must take the same
+    /// number of columns or the table falls out of line.
+    fn glyphs(&self, _segment: &BorderSegment) -> Option<String> {
+        None
+    }
+}
+
+impl Table {
+    pub fn set_border_painter(&mut self, painter: impl BorderPainter + 'static) {
+        self.border_painter = Some(Arc::new(painter));
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/clipboard.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::io;
+
+use crate::Table;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardFormat {
+    /// The table as `print` renders it, without colors.
+    Plain,
+    Markdown,
+    /// Tab-separated values, which paste into spreadsheets cell by cell.
+    Tsv,
+}
+
+impl Table {
+    pub fn copy_to_clipboard(&self, format: ClipboardFormat) -> io::Result<()> {
+        let text = match format {
+            ClipboardFormat::Plain => {
+                let mut buffer = Vec::new();
+                self.print_to_writer(&mut buffer)?;
+                String::from_utf8(buffer).map_err(io::Error::other)?
+            }
+            ClipboardFormat::Markdown => self.to_markdown(),
+            ClipboardFormat::Tsv => self.to_tsv(),
+        };
+        let mut clipboard = arboard::Clipboard::new().map_err(io::Error::other)?;
+        clipboard.set_text(text).map_err(io::Error::other)
+    }
+}
@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/color.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+//! Colors for themes and highlights. These are the crate's own types in
+//! every build, so turning the `color` feature on somewhere in the
+//! dependency graph does not change the API; with the feature they are
+//! drawn through termcolor, without it they are never drawn.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Blue,
+    Green,
+    Red,
+    Cyan,
+    Magenta,
+    Yellow,
+    White,
+    Ansi256(u8),
+    Rgb(u8, u8, u8),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColorSpec {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl ColorSpec {
+    pub fn new() -> Self {
+        ColorSpec::default()
+    }
+
+    pub fn set_fg(&mut self, color: Option<Color>) -> &mut Self {
+        self.fg = color;
+        self
+    }
+
+    pub fn set_bg(&mut self, color: Option<Color>) -> &mut Self {
+        self.bg = color;
+        self
+    }
+
+    pub fn set_bold(&mut self, yes: bool) -> &mut Self {
+        self.bold = yes;
+        self
+    }
+
+    pub fn set_dimmed(&mut self, yes: bool) -> &mut Self {
+        self.dimmed = yes;
+        self
+    }
+
+    pub fn set_italic(&mut self, yes: bool) -> &mut Self {
+        self.italic = yes;
+        self
+    }
+
+    pub fn set_underline(&mut self, yes: bool) -> &mut Self {
+        self.underline = yes;
+        self
+    }
+
+    pub fn fg(&self) -> Option<&Color> {
+        self.fg.as_ref()
+    }
+
+    pub fn bg(&self) -> Option<&Color> {
+        self.bg.as_ref()
+    }
+
+    pub fn bold(&self) -> bool {
+        self.bold
+    }
+
+    pub fn dimmed(&self) -> bool {
+        self.dimmed
+    }
+
+    pub fn italic(&self) -> bool {
+        self.italic
+    }
+
+    pub fn underline(&self) -> bool {
+        self.underline
+    }
+
+    #[cfg(feature = "color")]
+    pub(crate) fn to_termcolor(&self) -> termcolor::ColorSpec {
+        let mut spec = termcolor::ColorSpec::new();
+        spec.set_fg(self.fg.map(Color::to_termcolor))
+            .set_bg(self.bg.map(Color::to_termcolor))
+            .set_bold(self.bold)
+            .set_dimmed(self.dimmed)
+            .set_italic(self.italic)
+            .set_underline(self.underline);
+        spec
+    }
+}
+
+#[cfg(feature = "color")]
+impl Color {
+    fn to_termcolor(self) -> termcolor::Color {
+        match self {
+            Color::Black => termcolor::Color::Black,
+            Color::Blue => termcolor::Color::Blue,
+            Color::Green => termcolor::Color::Green,
+            Color::Red => termcolor::Color::Red,
+            Color::Cyan => termcolor::Color::Cyan,
+            Color::Magenta => termcolor::Color::Magenta,
+            Color::Yellow => termcolor::Color::Yellow,
+            Color::White => termcolor::Color::White,
+            Color::Ansi256(index) => termcolor::Color::Ansi256(index),
+            Color::Rgb(r, g, b) => termcolor::Color::Rgb(r, g, b),
+        }
+    }
+}
+
+/// A writer that takes colors, as `print_color` and `print_with` expect.
+/// Without the `color` feature every `Write` is one and colors are left
+/// out; with it this is termcolor's trait.
+#[cfg(not(feature = "color"))]
+pub trait WriteColor: std::io::Write {}
+
+#[cfg(not(feature = "color"))]
+impl<W: std::io::Write + ?Sized> WriteColor for W {}
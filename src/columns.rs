@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/columns.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::view::remap_cells;
+use crate::{Table, TableError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for ColumnRef {
+    fn from(index: usize) -> Self {
+        ColumnRef::Index(index)
+    }
+}
+
+impl From<&str> for ColumnRef {
+    fn from(name: &str) -> Self {
+        ColumnRef::Name(name.to_string())
+    }
+}
+
+impl From<String> for ColumnRef {
+    fn from(name: String) -> Self {
+        ColumnRef::Name(name)
+    }
+}
+
+impl Table {
+    /// Resolves a column by index or by header. A header shared by several
+    /// columns is an error rather than a guess.
+    pub fn column_index(&self, column: impl Into<ColumnRef>) -> Result<usize, TableError> {
+        match column.into() {
+            ColumnRef::Index(index) if index < self.columns.len() => Ok(index),
+            ColumnRef::Index(index) => Err(TableError::ColumnOutOfRange(index)),
+            ColumnRef::Name(name) => {
+                let mut matches =
+                    (0..self.columns.len()).filter(|&i| self.columns[i].header == name);
+                match (matches.next(), matches.next()) {
+                    (Some(index), None) => Ok(index),
+                    (Some(_), Some(_)) => Err(TableError::AmbiguousColumn(name)),
+                    (None, _) => Err(TableError::ColumnNotFound(name)),
+                }
+            }
+        }
+    }
+
+    /// `row` when the table has a row at that index.
+    pub(crate) fn row_index(&self, row: usize) -> Result<usize, TableError> {
+        if row < self.rows.len() {
+            Ok(row)
+        } else {
+            Err(TableError::RowOutOfRange(row))
+        }
+    }
+
+    pub fn rename_column(
+        &mut self,
+        column: impl Into<ColumnRef>,
+        header: &str,
+    ) -> Result<(), TableError> {
+        let index = self.column_index(column)?;
+        self.columns[index].header = header.to_string();
+        Ok(())
+    }
+
+    /// Removes a column and its cells. Fails with `ColumnInUse` when a
+    /// computed column lies further right, since it reads the cells to its
+    /// left by position and would see them shifted.
+    pub fn drop_column(&mut self, column: impl Into<ColumnRef>) -> Result<(), TableError> {
+        let index = self.column_index(column)?;
+        if let Some(computed) =
+            (index + 1..self.columns.len()).find(|&c| self.columns[c].compute.is_some())
+        {
+            return Err(TableError::ColumnInUse {
+                column: index,
+                computed,
+            });
+        }
+        self.columns.remove(index);
+        for row in self.rows.iter_mut().chain(&mut self.grand_total) {
+            row.remove(index);
+        }
+        if let Some(widths) = &mut self.locked_widths {
+            if index < widths.len() {
+                widths.remove(index);
+            }
+        }
+        self.invalidate_stats();
+        let shift =
+            |row, column| (column != index).then(|| (row, column - usize::from(column > index)));
+        self.cell_highlights = remap_cells(&self.cell_highlights, shift);
+        self.cell_meta = remap_cells(&self.cell_meta, shift);
+        self.footers.retain(|(c, _)| *c != index);
+        for (c, _) in &mut self.footers {
+            if *c > index {
+                *c -= 1;
+            }
+        }
+        Ok(())
+    }
+}
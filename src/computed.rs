@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/computed.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::sync::Arc;
+
+use crate::text::display_width;
+use crate::{Alignment, Column, RowView, Table};
+
+pub(crate) type ComputeFn = Arc<dyn Fn(&RowView) -> String + Send + Sync>;
+
+impl Table {
+    /// Appends a column whose cells are computed from the cells to its left.
+    ///
+    /// The closure runs once for every existing row and again for every row
+    /// added later; rows passed to `add_row` may omit computed cells.
+    pub fn add_computed_column<F>(&mut self, header: &str, compute: F)
+    where
+        F: Fn(&[String]) -> String + Send + Sync + 'static,
+    {
+        self.add_computed_column_with(header, move |row| compute(row.cells()));
+    }
+
+    /// Like `add_computed_column`, with the cells to the left looked up by
+    /// header, e.g. `row.get_f64("price")`.
+    pub fn add_computed_column_with<F>(&mut self, header: &str, compute: F)
+    where
+        F: Fn(&RowView) -> String + Send + Sync + 'static,
+    {
+        let compute: ComputeFn = Arc::new(compute);
+        for row in &mut self.rows {
+            let value = compute(&RowView::new(&self.columns, row, self.locale));
+            row.push(value);
+        }
+        let mut column = Column::new(header, display_width(header), Alignment::Left);
+        column.compute = Some(compute);
+        self.columns.push(column);
+        self.invalidate_stats();
+    }
+
+    pub(crate) fn complete_row(&self, row: Vec<String>) -> Vec<String> {
+        let row = self.fit_row(row);
+        let computed = self.columns.iter().filter(|c| c.compute.is_some()).count();
+        let omitted = row.len() + computed == self.columns.len();
+        if computed == 0 || !(omitted || row.len() == self.columns.len()) {
+            return row;
+        }
+        let mut cells = row.into_iter();
+        let mut full = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            match &column.compute {
+                Some(compute) => {
+                    if !omitted {
+                        cells.next();
+                    }
+                    let value = compute(&RowView::new(&self.columns, &full, self.locale));
+                    full.push(value);
+                }
+                None => full.extend(cells.next()),
+            }
+        }
+        full
+    }
+}
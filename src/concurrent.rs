@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/concurrent.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::{Table, TableError};
+
+/// A cloneable handle for collecting rows from several threads. Every clone
+/// feeds the same table.
+#[derive(Clone)]
+pub struct ConcurrentTableBuilder {
+    table: Arc<Mutex<Table>>,
+}
+
+impl ConcurrentTableBuilder {
+    /// Starts from `table`, keeping its columns, settings and existing rows.
+    pub fn new(table: Table) -> Self {
+        ConcurrentTableBuilder {
+            table: Arc::new(Mutex::new(table)),
+        }
+    }
+
+    pub fn push_row(&self, row: Vec<String>) -> Result<(), TableError> {
+        self.lock().try_add_row(row)
+    }
+
+    /// Pushes a batch of rows under a single lock, stopping at the first
+    /// rejected row.
+    pub fn push_rows<I>(&self, rows: I) -> Result<(), TableError>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        let mut table = self.lock();
+        for row in rows {
+            table.try_add_row(row)?;
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies the rows collected so far into an independent table that can
+    /// be rendered while workers keep pushing.
+    pub fn snapshot(&self) -> Table {
+        let table = self.lock();
+        let mut snapshot = table.empty_like();
+        snapshot.rows = table.rows.clone();
+        snapshot.violations = table.violations.clone();
+        snapshot.row_highlights = table.row_highlights.clone();
+        snapshot.cell_highlights = table.cell_highlights.clone();
+        snapshot.cell_meta = table.cell_meta.clone();
+        snapshot
+    }
+
+    /// Returns the collected table, or a snapshot if other handles are
+    /// still alive.
+    pub fn into_table(self) -> Table {
+        match Arc::try_unwrap(self.table) {
+            Ok(table) => table.into_inner().unwrap_or_else(PoisonError::into_inner),
+            Err(table) => ConcurrentTableBuilder { table }.snapshot(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Table> {
+        self.table.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
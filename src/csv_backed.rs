@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/csv_backed.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::ops::Range;
+
+use csv::{ByteRecord, Position};
+
+use crate::error::csv_error;
+use crate::{Alignment, CsvDialect, IndexFormat, RowPolicy, Table, TableStyle};
+
+/// A CSV file of any size, shown a page at a time. Opening it only records
+/// where each record starts; rows are read when a page asks for them.
+pub struct CsvBackedTable<R = BufReader<File>> {
+    reader: csv::Reader<R>,
+    headers: Vec<String>,
+    /// Byte offset of every data record.
+    offsets: Vec<u64>,
+    style: TableStyle,
+    index: Option<IndexFormat>,
+}
+
+impl CsvBackedTable {
+    pub fn open(path: &str) -> io::Result<Self> {
+        CsvBackedTable::open_with_dialect(path, CsvDialect::default())
+    }
+
+    pub fn open_with_dialect(path: &str, dialect: CsvDialect) -> io::Result<Self> {
+        CsvBackedTable::from_reader(BufReader::new(File::open(path)?), dialect)
+    }
+}
+
+impl<R: Read + Seek> CsvBackedTable<R> {
+    pub fn from_reader(reader: R, dialect: CsvDialect) -> io::Result<Self> {
+        // Headers are read by hand so every offset points at a data record.
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(dialect.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+        let mut headers = None;
+        let mut offsets = Vec::new();
+        let mut record = ByteRecord::new();
+        loop {
+            let start = reader.position().byte();
+            if !reader.read_byte_record(&mut record).map_err(csv_error)? {
+                break;
+            }
+            if headers.is_none() {
+                headers = Some(if dialect.has_headers {
+                    record_cells(&record)
+                } else {
+                    (1..=record.len())
+                        .map(|i| format!("Column {}", i))
+                        .collect()
+                });
+                if dialect.has_headers {
+                    continue;
+                }
+            }
+            offsets.push(start);
+        }
+        Ok(CsvBackedTable {
+            reader,
+            headers: headers.unwrap_or_default(),
+            offsets,
+            style: TableStyle::Simple,
+            index: None,
+        })
+    }
+
+    pub fn set_style(&mut self, style: TableStyle) {
+        self.style = style;
+    }
+
+    /// Numbers rows by their position in the file, so the index continues
+    /// from one page to the next.
+    pub fn show_index(&mut self, format: IndexFormat) {
+        self.index = Some(format);
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Number of data records.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    pub fn page_count(&self, page_size: usize) -> usize {
+        self.len().div_ceil(page_size.max(1))
+    }
+
+    /// Reads the records in `range`, clamped to the file.
+    pub fn rows(&mut self, range: Range<usize>) -> io::Result<Vec<Vec<String>>> {
+        let end = range.end.min(self.len());
+        let Some(&start) = self.offsets.get(range.start).filter(|_| range.start < end) else {
+            return Ok(Vec::new());
+        };
+        let mut position = Position::new();
+        position.set_byte(start).set_record(range.start as u64);
+        self.reader.seek(position).map_err(csv_error)?;
+        let mut rows = Vec::with_capacity(end - range.start);
+        let mut record = ByteRecord::new();
+        while rows.len() < end - range.start
+            && self
+                .reader
+                .read_byte_record(&mut record)
+                .map_err(csv_error)?
+        {
+            rows.push(record_cells(&record));
+        }
+        Ok(rows)
+    }
+
+    /// Page `page`, counting from 0, as a table of its own. Short records
+    /// are padded and long ones cut to the header row.
+    pub fn page(&mut self, page: usize, page_size: usize) -> io::Result<Table> {
+        let start = page.saturating_mul(page_size);
+        let rows = self.rows(start..start.saturating_add(page_size))?;
+        let mut table = Table::new(self.style);
+        table.set_row_policy(RowPolicy::PadOrTruncate);
+        for header in &self.headers {
+            table.add_column(header, 10, Alignment::Left);
+        }
+        for row in rows {
+            table.add_row(row);
+        }
+        if let Some(format) = self.index {
+            table.show_index(format);
+            table.set_index_start(start + 1);
+        }
+        Ok(table)
+    }
+}
+
+fn record_cells(record: &ByteRecord) -> Vec<String> {
+    record
+        .iter()
+        .map(|field| String::from_utf8_lossy(field).into_owned())
+        .collect()
+}
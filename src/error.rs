@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/error.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::{fmt, io};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    ColumnOutOfRange(usize),
+    RowOutOfRange(usize),
+    ColumnNotFound(String),
+    /// More than one column has this header.
+    AmbiguousColumn(String),
+    /// The column, or one to its left, may be an input of the computed
+    /// column `computed`.
+    ColumnInUse {
+        column: usize,
+        computed: usize,
+    },
+    NotNumeric {
+        row: usize,
+        column: usize,
+        value: String,
+    },
+    NoValues {
+        column: usize,
+    },
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        row: usize,
+        column: usize,
+        value: String,
+        reason: String,
+    },
+    InvalidExpression {
+        position: usize,
+        message: String,
+    },
+    /// A malformed CSV record; `record` and `line` are 1-based and count the
+    /// header row, `byte` is the offset of the record start.
+    Csv {
+        record: u64,
+        line: u64,
+        byte: u64,
+        field: Option<usize>,
+        message: String,
+    },
+    /// Reading or writing failed, e.g. because a file does not exist.
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::ColumnOutOfRange(column) => {
+                write!(f, "column index {} is out of range", column)
+            }
+            TableError::RowOutOfRange(row) => write!(f, "row index {} is out of range", row),
+            TableError::ColumnNotFound(name) => write!(f, "no column named {:?}", name),
+            TableError::AmbiguousColumn(name) => {
+                write!(f, "more than one column is named {:?}", name)
+            }
+            TableError::ColumnInUse { column, computed } => write!(
+                f,
+                "column {} may be an input of computed column {}",
+                column, computed
+            ),
+            TableError::NotNumeric { row, column, value } => write!(
+                f,
+                "row {}, column {}: {:?} is not a number",
+                row, column, value
+            ),
+            TableError::NoValues { column } => {
+                write!(f, "column {} has no values to aggregate", column)
+            }
+            TableError::RowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {}: expected {} cells, found {}",
+                row, expected, found
+            ),
+            TableError::InvalidCell {
+                row,
+                column,
+                value,
+                reason,
+            } => write!(
+                f,
+                "row {}, column {}: {:?} is invalid, {}",
+                row, column, value, reason
+            ),
+            TableError::InvalidExpression { position, message } => {
+                write!(f, "invalid expression at offset {}: {}", position, message)
+            }
+            TableError::Csv {
+                record,
+                line,
+                byte,
+                field,
+                message,
+            } => {
+                write!(f, "CSV record {} (line {}, byte {})", record, line, byte)?;
+                if let Some(field) = field {
+                    write!(f, ", field {}", field)?;
+                }
+                write!(f, ": {}", message)
+            }
+            TableError::Io { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<TableError> for io::Error {
+    fn from(error: TableError) -> Self {
+        match error {
+            TableError::Io { kind, message } => io::Error::new(kind, message),
+            error => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// Unwraps a `TableError` carried by the I/O error, such as a CSV record
+/// position, and keeps the kind and message of any other.
+impl From<io::Error> for TableError {
+    fn from(error: io::Error) -> Self {
+        match error.get_ref().and_then(|inner| inner.downcast_ref()) {
+            Some(inner) => TableError::clone(inner),
+            None => TableError::Io {
+                kind: error.kind(),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// Converts a csv error into an `io::Error`, keeping I/O failures as they
+/// are and attaching the record position to everything else.
+#[cfg(feature = "csv")]
+pub(crate) fn csv_error(error: csv::Error) -> io::Error {
+    let position = error.position().cloned();
+    let (field, message) = match error.kind() {
+        csv::ErrorKind::Utf8 { err, .. } => (
+            Some(err.field()),
+            format!("invalid UTF-8 after {} valid bytes", err.valid_up_to()),
+        ),
+        csv::ErrorKind::UnequalLengths {
+            expected_len, len, ..
+        } => (
+            None,
+            format!("expected {} fields, found {}", expected_len, len),
+        ),
+        csv::ErrorKind::Deserialize { err, .. } => {
+            (err.field().map(|f| f as usize), err.to_string())
+        }
+        _ => (None, error.to_string()),
+    };
+    let Some(position) = position else {
+        return io::Error::from(error);
+    };
+    TableError::Csv {
+        record: position.record() + 1,
+        line: position.line(),
+        byte: position.byte(),
+        field,
+        message,
+    }
+    .into()
+}
+
+/// The rows rejected by `try_add_rows`, keyed by their position in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowErrors {
+    pub(crate) errors: Vec<(usize, TableError)>,
+}
+
+impl RowErrors {
+    pub fn errors(&self) -> &[(usize, TableError)] {
+        &self.errors
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for RowErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} rows rejected", self.errors.len())?;
+        for (index, error) in &self.errors {
+            write!(f, "\n  input row {}: {}", index, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RowErrors {}
+
+impl From<RowErrors> for io::Error {
+    fn from(error: RowErrors) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// A name that does not match any variant, e.g. an unknown `--style`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    pub(crate) kind: &'static str,
+    pub(crate) value: String,
+    pub(crate) expected: Vec<&'static str>,
+}
+
+impl ParseEnumError {
+    /// The accepted names, in declaration order.
+    pub fn expected(&self) -> &[&'static str] {
+        &self.expected
+    }
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown {} '{}', expected one of: {}",
+            self.kind,
+            self.value,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/export.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::io::{self, Write};
+
+use crate::{Alignment, Table};
+
+impl Table {
+    /// A Markdown table. Column descriptions become footnotes on the
+    /// headers.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let mut notes = Vec::new();
+        let headers: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| match &c.description {
+                Some(description) => {
+                    notes.push(description);
+                    format!("{}[^{}]", markdown_cell(&c.header), notes.len())
+                }
+                None => markdown_cell(&c.header),
+            })
+            .collect();
+        push_markdown_row(&mut out, &headers);
+        let rules: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| match c.alignment {
+                Alignment::Left => ":---".to_string(),
+                Alignment::Center => ":---:".to_string(),
+                Alignment::Right => "---:".to_string(),
+            })
+            .collect();
+        push_markdown_row(&mut out, &rules);
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|c| markdown_cell(c)).collect();
+            push_markdown_row(&mut out, &cells);
+        }
+        if !notes.is_empty() {
+            out.push('\n');
+        }
+        for (i, note) in notes.iter().enumerate() {
+            out.push_str(&format!("[^{}]: {}\n", i + 1, note.replace('\n', " ")));
+        }
+        out
+    }
+
+    /// Tab-separated values with a header line; tabs and line breaks inside
+    /// cells become spaces.
+    pub fn to_tsv(&self) -> String {
+        let mut out = String::new();
+        let headers = self.columns.iter().map(|c| c.header.as_str());
+        push_tsv_row(&mut out, headers);
+        for row in &self.rows {
+            push_tsv_row(&mut out, row.iter().map(String::as_str));
+        }
+        out
+    }
+
+    /// Writes each row as one record of exactly `widths` bytes per field,
+    /// without header or separators. Longer cells are cut at a character
+    /// boundary; right-aligned columns are padded on the left.
+    pub fn to_fixed_width(&self, writer: &mut dyn Write, widths: &[usize]) -> io::Result<()> {
+        if widths.len() != self.columns.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "expected {} field widths, got {}",
+                    self.columns.len(),
+                    widths.len()
+                ),
+            ));
+        }
+        let mut record = Vec::with_capacity(widths.iter().sum::<usize>() + 1);
+        for row in &self.rows {
+            record.clear();
+            for ((cell, column), &width) in row.iter().zip(&self.columns).zip(widths) {
+                let cell = cell.replace(['\n', '\r'], " ");
+                let mut end = cell.len().min(width);
+                while !cell.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let fill = vec![b' '; width - end];
+                if matches!(column.alignment, Alignment::Right) {
+                    record.extend_from_slice(&fill);
+                    record.extend_from_slice(&cell.as_bytes()[..end]);
+                } else {
+                    record.extend_from_slice(&cell.as_bytes()[..end]);
+                    record.extend_from_slice(&fill);
+                }
+            }
+            record.push(b'\n');
+            writer.write_all(&record)?;
+        }
+        Ok(())
+    }
+}
+
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn push_markdown_row(out: &mut String, cells: &[String]) {
+    out.push('|');
+    for cell in cells {
+        out.push(' ');
+        out.push_str(cell);
+        out.push_str(" |");
+    }
+    out.push('\n');
+}
+
+fn push_tsv_row<'a>(out: &mut String, cells: impl Iterator<Item = &'a str>) {
+    let cells: Vec<String> = cells.map(|c| c.replace(['\t', '\n', '\r'], " ")).collect();
+    out.push_str(&cells.join("\t"));
+    out.push('\n');
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/exporter.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use crate::Table;
+
+/// An output format. Closures taking the table and a writer are exporters
+/// too.
+pub trait Exporter: Send + Sync {
+    fn export(&self, table: &Table, writer: &mut dyn Write) -> io::Result<()>;
+}
+
+impl<F> Exporter for F
+where
+    F: Fn(&Table, &mut dyn Write) -> io::Result<()> + Send + Sync,
+{
+    fn export(&self, table: &Table, writer: &mut dyn Write) -> io::Result<()> {
+        self(table, writer)
+    }
+}
+
+/// Exporters by name, for choosing an output format at runtime, e.g. from
+/// a `--format` flag.
+pub struct Exporters {
+    exporters: BTreeMap<String, Box<dyn Exporter>>,
+}
+
+impl Exporters {
+    /// The built-in formats: `text`, `markdown`, `tsv` and `html`, plus
+    /// `csv` and `json` when those features are enabled.
+    pub fn new() -> Self {
+        let mut exporters = Exporters::empty();
+        exporters.register("text", |table: &Table, writer: &mut dyn Write| {
+            table.print_to_writer(writer)
+        });
+        exporters.register("markdown", |table: &Table, writer: &mut dyn Write| {
+            writer.write_all(table.to_markdown().as_bytes())
+        });
+        exporters.register("tsv", |table: &Table, writer: &mut dyn Write| {
+            writer.write_all(table.to_tsv().as_bytes())
+        });
+        exporters.register("html", |table: &Table, writer: &mut dyn Write| {
+            writer.write_all(table.to_html().as_bytes())
+        });
+        #[cfg(feature = "csv")]
+        exporters.register("csv", export_csv);
+        #[cfg(feature = "json")]
+        exporters.register("json", export_json);
+        exporters
+    }
+
+    pub fn empty() -> Self {
+        Exporters {
+            exporters: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a format, replacing any registered under the same name.
+    pub fn register(&mut self, name: &str, exporter: impl Exporter + 'static) {
+        self.exporters.insert(name.to_string(), Box::new(exporter));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Exporter> {
+        self.exporters.get(name).map(|exporter| &**exporter)
+    }
+
+    /// Registered names in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.exporters.keys().map(String::as_str)
+    }
+
+    pub fn export(&self, name: &str, table: &Table, writer: &mut dyn Write) -> io::Result<()> {
+        match self.get(name) {
+            Some(exporter) => exporter.export(table, writer),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "unknown format `{}`, expected one of: {}",
+                    name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                ),
+            )),
+        }
+    }
+}
+
+impl Default for Exporters {
+    fn default() -> Self {
+        Exporters::new()
+    }
+}
+
+/// Headers, then rows.
+#[cfg(feature = "csv")]
+fn export_csv(table: &Table, writer: &mut dyn Write) -> io::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(table.columns.iter().map(|column| &column.header))?;
+    for row in &table.rows {
+        csv.write_record(row)?;
+    }
+    csv.flush()
+}
+
+/// An array with one object per row, keyed by header, as
+/// `Table::from_json_reader` reads it.
+#[cfg(feature = "json")]
+fn export_json(table: &Table, writer: &mut dyn Write) -> io::Result<()> {
+    let rows: Vec<serde_json::Map<String, serde_json::Value>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, cell)| (column.header.clone(), cell.as_str().into()))
+                .collect()
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut *writer, &rows)?;
+    writer.write_all(b"\n")
+}
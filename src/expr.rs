@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/expr.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::cmp::Ordering;
+
+use crate::aggregate::{format_number, parse_number};
+use crate::{Locale, Table, TableError};
+
+impl Table {
+    /// Keeps the rows matching `expr`, e.g. `age > 30 && city != 'NYC'`.
+    ///
+    /// Columns are referenced by header, in backticks when the header is not
+    /// a plain identifier. Values compare numerically when both sides are
+    /// numbers, with cells read in their column's locale, and as text
+    /// otherwise; a number never matches a cell without one, so `age > 30`
+    /// skips blank and `n/a` cells. Besides the usual comparisons,
+    /// `a contains 'x'` tests for a substring and, with the `regex` feature,
+    /// `a ~ '^x'` for a pattern match. Conditions combine with `&&`, `||`,
+    /// `!` and parentheses, nested at most 64 levels deep.
+    pub fn filter_expr(&self, expr: &str) -> Result<Table, TableError> {
+        let expr = Parser::new(expr, self)?.parse()?;
+        let indices: Vec<usize> = (0..self.rows.len())
+            .filter(|&i| expr.eval(&self.rows[i]).truthy())
+            .collect();
+        Ok(self.select_rows(indices))
+    }
+
+    /// Like `add_computed_column`, with the cells computed from an expression
+    /// such as `price * qty`. Arithmetic supports `+ - * / %` and
+    /// parentheses; `+` joins text when either side is not a number.
+    pub fn add_computed_column_expr(&mut self, header: &str, expr: &str) -> Result<(), TableError> {
+        let expr = Parser::new(expr, self)?.parse()?;
+        self.add_computed_column(header, move |row| expr.eval(row).text());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Number(f64),
+    Text(String),
+    /// A cell's text, read as a number with its column's locale.
+    Cell(String, Locale),
+    Bool(bool),
+}
+
+impl Value {
+    fn number(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            // "NaN" and "inf" are names, not numbers.
+            Value::Text(s) => parse_number(s).filter(|n| n.is_finite()),
+            Value::Cell(s, locale) => locale.parse(s).filter(|n| n.is_finite()),
+            Value::Bool(_) => None,
+        }
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Value::Number(n) => format_number(*n),
+            Value::Text(s) | Value::Cell(s, _) => s.clone(),
+            Value::Bool(b) => b.to_string(),
+        }
+    }
+
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Number(n) => *n != 0.0,
+            Value::Text(s) | Value::Cell(s, _) => !s.is_empty(),
+            Value::Bool(b) => *b,
+        }
+    }
+
+    /// `None` when the values do not compare, as a number against a cell
+    /// that holds none, e.g. `age > 30` on a blank or `n/a` cell.
+    fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self.number(), other.number()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ if matches!(self, Value::Number(_)) || matches!(other, Value::Number(_)) => None,
+            _ => Some(self.text().cmp(&other.text())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    /// Values that do not compare are unequal and in no order.
+    fn test(self, ordering: Option<Ordering>) -> bool {
+        let Some(ordering) = ordering else {
+            return self == CompareOp::Ne;
+        };
+        match self {
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl ArithOp {
+    fn apply(self, a: &Value, b: &Value) -> Value {
+        let (Some(x), Some(y)) = (a.number(), b.number()) else {
+            return match self {
+                ArithOp::Add => Value::Text(a.text() + &b.text()),
+                _ => Value::Text(String::new()),
+            };
+        };
+        Value::Number(match self {
+            ArithOp::Add => x + y,
+            ArithOp::Sub => x - y,
+            ArithOp::Mul => x * y,
+            ArithOp::Div => x / y,
+            ArithOp::Rem => x % y,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Expr {
+    Column(usize, Locale),
+    Literal(Value),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    Contains(Box<Expr>, Box<Expr>),
+    #[cfg(feature = "regex")]
+    Matches(Box<Expr>, regex::Regex),
+    /// Operands of a chain of `&&`, kept flat so long chains stay shallow.
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Arith(Box<Expr>, ArithOp, Box<Expr>),
+    Neg(Box<Expr>),
+}
+
+impl Expr {
+    pub(crate) fn eval(&self, row: &[String]) -> Value {
+        match self {
+            Expr::Column(index, locale) => {
+                Value::Cell(row.get(*index).cloned().unwrap_or_default(), *locale)
+            }
+            Expr::Literal(value) => value.clone(),
+            Expr::Compare(a, op, b) => Value::Bool(op.test(a.eval(row).compare(&b.eval(row)))),
+            Expr::Contains(a, b) => Value::Bool(a.eval(row).text().contains(&b.eval(row).text())),
+            #[cfg(feature = "regex")]
+            Expr::Matches(a, pattern) => Value::Bool(pattern.is_match(&a.eval(row).text())),
+            Expr::And(terms) => Value::Bool(terms.iter().all(|t| t.eval(row).truthy())),
+            Expr::Or(terms) => Value::Bool(terms.iter().any(|t| t.eval(row).truthy())),
+            Expr::Not(a) => Value::Bool(!a.eval(row).truthy()),
+            Expr::Arith(a, op, b) => op.apply(&a.eval(row), &b.eval(row)),
+            Expr::Neg(a) => a
+                .eval(row)
+                .number()
+                .map_or(Value::Text(String::new()), |n| Value::Number(-n)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Text(String),
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 18] = [
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "=", "!", "~", "(", ")", "+", "-", "*", "/", "%",
+];
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, TableError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' || c == '`' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, q)) if q == c => break,
+                    Some((_, '\\')) => text.extend(chars.next().map(|(_, e)| e)),
+                    Some((_, other)) => text.push(other),
+                    None => return Err(expr_error(start, "unterminated quote")),
+                }
+            }
+            tokens.push((
+                start,
+                if c == '`' {
+                    Token::Ident(text)
+                } else {
+                    Token::Text(text)
+                },
+            ));
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            while let Some(&(i, d)) = chars.peek() {
+                if !(d.is_ascii_digit() || d == '.' || d == '_') {
+                    break;
+                }
+                end = i + d.len_utf8();
+                chars.next();
+            }
+            let number = parse_number(&input[start..end])
+                .ok_or_else(|| expr_error(start, "invalid number"))?;
+            tokens.push((start, Token::Number(number)));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, d)) = chars.peek() {
+                if !(d.is_alphanumeric() || d == '_') {
+                    break;
+                }
+                end = i + d.len_utf8();
+                chars.next();
+            }
+            tokens.push((start, Token::Ident(input[start..end].to_string())));
+        } else {
+            let op = OPERATORS
+                .iter()
+                .find(|op| input[start..].starts_with(**op))
+                .ok_or_else(|| expr_error(start, &format!("unexpected {:?}", c)))?;
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push((start, Token::Op(op)));
+        }
+    }
+    Ok(tokens)
+}
+
+fn expr_error(position: usize, message: &str) -> TableError {
+    TableError::InvalidExpression {
+        position,
+        message: message.to_string(),
+    }
+}
+
+/// How deeply expressions may nest, counting parentheses, prefix operators
+/// and chained arithmetic. Deeper input is rejected rather than overflowing
+/// the stack while parsing it; the limit leaves room on a 2 MiB thread
+/// stack even in debug builds.
+const MAX_DEPTH: usize = 64;
+
+pub(crate) struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    end: usize,
+    depth: usize,
+    table: &'a Table,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(input: &str, table: &'a Table) -> Result<Self, TableError> {
+        Ok(Parser {
+            tokens: tokenize(input)?,
+            next: 0,
+            end: input.len(),
+            depth: 0,
+            table,
+        })
+    }
+
+    pub(crate) fn parse(mut self) -> Result<Expr, TableError> {
+        let expr = self.or()?;
+        match self.tokens.get(self.next) {
+            Some((position, token)) => {
+                Err(expr_error(*position, &format!("unexpected {:?}", token)))
+            }
+            None => Ok(expr),
+        }
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.next).map_or(self.end, |(p, _)| *p)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Op(o)) if *o == op);
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    /// Enters one level of nesting. Callers restore `depth` once the
+    /// level's expression is built.
+    fn descend(&mut self) -> Result<(), TableError> {
+        if self.depth == MAX_DEPTH {
+            return Err(expr_error(self.position(), "expression nested too deeply"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn or(&mut self) -> Result<Expr, TableError> {
+        let mut terms = vec![self.and()?];
+        while self.eat_op("||") {
+            terms.push(self.and()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::Or(terms),
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, TableError> {
+        let mut terms = vec![self.unary()?];
+        while self.eat_op("&&") {
+            terms.push(self.unary()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::And(terms),
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, TableError> {
+        if self.eat_op("!") {
+            self.descend()?;
+            let expr = Expr::Not(Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, TableError> {
+        let left = self.sum()?;
+        let position = self.position();
+        let op = match self.peek() {
+            Some(Token::Op("==" | "=")) => CompareOp::Eq,
+            Some(Token::Op("!=")) => CompareOp::Ne,
+            Some(Token::Op("<")) => CompareOp::Lt,
+            Some(Token::Op("<=")) => CompareOp::Le,
+            Some(Token::Op(">")) => CompareOp::Gt,
+            Some(Token::Op(">=")) => CompareOp::Ge,
+            Some(Token::Ident(word)) if word == "contains" => {
+                self.next += 1;
+                return Ok(Expr::Contains(Box::new(left), Box::new(self.sum()?)));
+            }
+            Some(Token::Op("~")) => {
+                self.next += 1;
+                return self.matches(left, position);
+            }
+            _ => return Ok(left),
+        };
+        self.next += 1;
+        Ok(Expr::Compare(Box::new(left), op, Box::new(self.sum()?)))
+    }
+
+    fn sum(&mut self) -> Result<Expr, TableError> {
+        let depth = self.depth;
+        let mut expr = self.product()?;
+        loop {
+            let op = if self.eat_op("+") {
+                ArithOp::Add
+            } else if self.eat_op("-") {
+                ArithOp::Sub
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            };
+            self.descend()?;
+            expr = Expr::Arith(Box::new(expr), op, Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, TableError> {
+        let depth = self.depth;
+        let mut expr = self.operand()?;
+        loop {
+            let op = if self.eat_op("*") {
+                ArithOp::Mul
+            } else if self.eat_op("/") {
+                ArithOp::Div
+            } else if self.eat_op("%") {
+                ArithOp::Rem
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            };
+            self.descend()?;
+            expr = Expr::Arith(Box::new(expr), op, Box::new(self.operand()?));
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    fn matches(&mut self, left: Expr, _: usize) -> Result<Expr, TableError> {
+        let position = self.position();
+        let Some(Token::Text(pattern)) = self.peek().cloned() else {
+            return Err(expr_error(position, "expected a quoted pattern"));
+        };
+        self.next += 1;
+        let pattern =
+            regex::Regex::new(&pattern).map_err(|e| expr_error(position, &e.to_string()))?;
+        Ok(Expr::Matches(Box::new(left), pattern))
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn matches(&mut self, _: Expr, position: usize) -> Result<Expr, TableError> {
+        Err(expr_error(
+            position,
+            "pattern matching requires the `regex` feature",
+        ))
+    }
+
+    fn operand(&mut self) -> Result<Expr, TableError> {
+        let position = self.position();
+        if self.eat_op("(") {
+            self.descend()?;
+            let expr = self.or()?;
+            if !self.eat_op(")") {
+                return Err(expr_error(self.position(), "expected ')'"));
+            }
+            self.depth -= 1;
+            return Ok(expr);
+        }
+        if self.eat_op("-") {
+            self.descend()?;
+            let expr = Expr::Neg(Box::new(self.operand()?));
+            self.depth -= 1;
+            return Ok(expr);
+        }
+        let Some((_, token)) = self.tokens.get(self.next).cloned() else {
+            return Err(expr_error(position, "unexpected end of expression"));
+        };
+        self.next += 1;
+        match token {
+            Token::Number(n) => Ok(Expr::Literal(Value::Number(n))),
+            Token::Text(s) => Ok(Expr::Literal(Value::Text(s))),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                _ => {
+                    let index = self.table.column_index(name)?;
+                    Ok(Expr::Column(index, self.table.locale_of(index)))
+                }
+            },
+            Token::Op(op) => Err(expr_error(position, &format!("unexpected {:?}", op))),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/fast.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::io::{self, Write};
+
+use crate::render::{PlainSink, Sink};
+use crate::text::display_width;
+use crate::{
+    Alignment, EmptyPolicy, Format, HeaderOrientation, RenderOptions, Table, TableStyle, Wrap,
+};
+
+/// Bytes collected before they are handed to the writer.
+const FLUSH_AT: usize = 64 * 1024;
+
+impl Table {
+    /// Whether `write_fast` renders exactly what the general renderer would:
+    /// a Simple table of plain single-line cells that need no wrapping,
+    /// truncation or decoration.
+    pub(crate) fn fast_path(&self, options: &RenderOptions) -> bool {
+        #[cfg(feature = "bidi")]
+        if !matches!(self.bidi, crate::Bidi::Off) {
+            return false;
+        }
+        #[cfg(feature = "regex")]
+        if self
+            .columns
+            .iter()
+            .any(|column| !column.highlights.is_empty())
+        {
+            return false;
+        }
+        matches!(options.style, TableStyle::Simple)
+            && options.machine.is_none()
+            && !options.right_to_left
+            && options.repeat_header_every.is_none()
+            && options.max_width.is_none()
+            && self.total_width.is_none()
+            && self.title.is_none()
+            && self.index.is_none()
+            && self.locked_widths.is_none()
+            && self.footers.is_empty()
+            && self.grand_total.is_none()
+            && self.breakpoints.is_empty()
+            && !self.column_paging
+            && (!self.rows.is_empty() || self.empty_policy == EmptyPolicy::ShowHeaders)
+            && matches!(self.header_orientation, HeaderOrientation::Horizontal)
+            && self.columns.iter().all(|column| {
+                column.wrap == Wrap::Off
+                    && matches!(column.format, Format::Plain)
+                    && column.priority.is_none()
+                    && column.link_template.is_none()
+                    && !column.raw
+                    && column.unit.is_none()
+                    && !column.header.contains('\n')
+            })
+            && !self
+                .visible_rows()
+                .iter()
+                .any(|row| row.iter().any(|cell| cell.contains('\n')))
+    }
+
+    /// Renders like `render_with` for tables `fast_path` accepts, padding
+    /// cells by hand into one reused buffer.
+    pub(crate) fn write_fast(
+        &self,
+        writer: &mut dyn Write,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let widths = self.resolve_widths(options);
+        let mut buffer = Vec::with_capacity(FLUSH_AT + 1024);
+        if options.show_header {
+            for (i, (column, &width)) in self.columns.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    buffer.push(b' ');
+                }
+                let alignment = column.header_alignment.unwrap_or(column.alignment);
+                push_padded(&mut buffer, &column.header, width, alignment);
+            }
+            trim_line(&mut buffer, 0, options);
+            buffer.push(b'\n');
+        }
+        for row in self.visible_rows() {
+            let start = buffer.len();
+            for (i, (cell, &width)) in row.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    buffer.push(b' ');
+                }
+                push_padded(&mut buffer, cell, width, self.columns[i].alignment);
+            }
+            buffer.push(b' ');
+            trim_line(&mut buffer, start, options);
+            buffer.push(b'\n');
+            if buffer.len() >= FLUSH_AT {
+                writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+        writer.write_all(&buffer)?;
+        let mut sink = PlainSink(writer);
+        self.render_hidden_rows(&mut sink, options)?;
+        self.render_legend(&mut sink, options)?;
+        for _ in 0..options.trailing_newlines {
+            sink.end_line()?;
+        }
+        Ok(())
+    }
+}
+
+fn trim_line(buffer: &mut Vec<u8>, start: usize, options: &RenderOptions) {
+    if options.trim_trailing_spaces {
+        let kept = buffer[start..].iter().rposition(|&b| b != b' ');
+        buffer.truncate(start + kept.map_or(0, |i| i + 1));
+    }
+}
+
+fn push_padded(buffer: &mut Vec<u8>, cell: &str, width: usize, alignment: Alignment) {
+    let printable_ascii = cell.bytes().all(|b| (b' '..=b'~').contains(&b));
+    let used = if printable_ascii {
+        cell.len()
+    } else {
+        display_width(cell)
+    };
+    let fill = width.saturating_sub(used);
+    let (left, right) = match alignment {
+        Alignment::Left => (0, fill),
+        Alignment::Center => (fill / 2, fill - fill / 2),
+        Alignment::Right => (fill, 0),
+    };
+    buffer.extend(std::iter::repeat_n(b' ', left));
+    buffer.extend_from_slice(cell.as_bytes());
+    buffer.extend(std::iter::repeat_n(b' ', right));
+}
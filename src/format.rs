@@ -0,0 +1,457 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/format.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use crate::text::display_width;
+use crate::{Charset, Column, ColumnRef, Locale, Table, TableError};
+
+/// How a column's cells are displayed. Only the rendering changes; sorting,
+/// filters and exports still see the raw cell text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Plain,
+    /// Cells hold a percentage such as `73` or `73%`. With `bar`, a bar
+    /// filling the rest of the column follows the number.
+    Percent { bar: bool },
+    /// Byte counts in binary units: `1536` becomes `1.5 KiB`.
+    Bytes,
+    /// Large counts with a suffix: `1234567` becomes `1.2M`.
+    Count,
+    /// Durations shown as `2h 13m 05s`. Numeric cells count `unit`s; ISO 8601
+    /// durations such as `PT2H13M5S` are read as well.
+    Duration { unit: DurationUnit },
+    /// Scientific notation with `precision` decimals: `1.23e-05`.
+    Scientific { precision: usize },
+    /// Rounded to this many significant figures, switching to scientific
+    /// notation for very small or very large magnitudes.
+    SigFigs(usize),
+    /// A fixed number of decimals with the locale's digit grouping.
+    Fixed { decimals: usize },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DurationUnit {
+    #[default]
+    Seconds,
+    Millis,
+}
+
+/// Bar length used when measuring a column's natural width.
+const BAR_WIDTH: usize = 10;
+
+/// Width the percentage in front of a bar is padded to, enough for "100%",
+/// so every bar in a column starts in the same place and has the same room.
+const PERCENT_WIDTH: usize = 4;
+
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+impl Format {
+    /// Display text for `cell` in a column `width` wide, or at its natural
+    /// width when `None`. Cells that are not numbers are shown unchanged.
+    pub(crate) fn apply<'a>(
+        &self,
+        cell: &'a str,
+        width: Option<usize>,
+        charset: Charset,
+        locale: Locale,
+    ) -> Cow<'a, str> {
+        match *self {
+            Format::Plain => Cow::Borrowed(cell),
+            Format::Percent { bar } => {
+                let Some(value) = locale.parse(cell.trim().trim_end_matches('%')) else {
+                    return Cow::Borrowed(cell);
+                };
+                let text = format!("{}%", locale.format(value, 0));
+                if !bar {
+                    return Cow::Owned(text);
+                }
+                let room = width.map_or(BAR_WIDTH, |w| w.saturating_sub(PERCENT_WIDTH + 1));
+                Cow::Owned(format!(
+                    "{:>width$} {}",
+                    text,
+                    draw_bar(value / 100.0, room, charset),
+                    width = PERCENT_WIDTH
+                ))
+            }
+            Format::Bytes | Format::Count => match self.value(cell, locale) {
+                Some(value) if *self == Format::Bytes => {
+                    Cow::Owned(scale(value, &BYTE_UNITS, locale))
+                }
+                Some(value) => Cow::Owned(scale(value, &COUNT_UNITS, locale)),
+                None => Cow::Borrowed(cell),
+            },
+            Format::Duration { .. } => match self.value(cell, locale) {
+                Some(seconds) => Cow::Owned(humanize_duration(seconds)),
+                None => Cow::Borrowed(cell),
+            },
+            Format::Scientific { precision } => match locale.parse(cell) {
+                Some(value) => Cow::Owned(locale.localize(&scientific(value, precision))),
+                None => Cow::Borrowed(cell),
+            },
+            Format::SigFigs(digits) => match locale.parse(cell) {
+                Some(value) => Cow::Owned(locale.localize(&significant(value, digits.max(1)))),
+                None => Cow::Borrowed(cell),
+            },
+            Format::Fixed { decimals } => match locale.parse(cell) {
+                Some(value) => Cow::Owned(locale.format(value, decimals)),
+                None => Cow::Borrowed(cell),
+            },
+        }
+    }
+
+    /// The number in `text` written to fit `width`: with fewer decimals
+    /// first, then in scientific notation. `None` when nothing fits or the
+    /// text is not a number with a fraction or exponent; plain whole
+    /// numbers such as IDs are never rewritten.
+    fn shrink(&self, text: &str, width: usize, locale: Locale) -> Option<String> {
+        if !matches!(
+            self,
+            Format::Plain | Format::Fixed { .. } | Format::Scientific { .. } | Format::SigFigs(_)
+        ) {
+            return None;
+        }
+        let value = locale.parse(text).filter(|value| value.is_finite())?;
+        let exponent = text.contains(['e', 'E']);
+        let mantissa = text.split(['e', 'E']).next().unwrap_or(text);
+        let decimals = mantissa
+            .rsplit_once(locale.decimal)
+            .map_or(0, |(_, d)| d.len());
+        if decimals == 0 && !exponent && *self == Format::Plain {
+            return None;
+        }
+        let fixed = match self {
+            Format::Fixed { .. } => locale,
+            _ => Locale {
+                grouping: None,
+                ..locale
+            },
+        };
+        let fewer_decimals = (0..decimals)
+            .rev()
+            .filter(|_| !exponent)
+            .map(|decimals| fixed.format(value, decimals));
+        let precisions = if exponent { decimals } else { 4 };
+        let scientific = (0..precisions)
+            .rev()
+            .map(|precision| locale.localize(&scientific(value, precision)));
+        fewer_decimals
+            .chain(scientific)
+            .find(|candidate| display_width(candidate) <= width)
+    }
+
+    /// The number behind a cell, understanding the units this format
+    /// produces, so `1.5 KiB` reads as 1536 in a `Bytes` column.
+    pub(crate) fn value(&self, cell: &str, locale: Locale) -> Option<f64> {
+        let cell = cell.trim();
+        if let Format::Duration { unit } = self {
+            let scale = match unit {
+                DurationUnit::Seconds => 1.0,
+                DurationUnit::Millis => 0.001,
+            };
+            return locale
+                .parse(cell)
+                .map(|n| n * scale)
+                .or_else(|| parse_duration(cell));
+        }
+        let units: &[(&str, f64)] = match self {
+            Format::Plain
+            | Format::Scientific { .. }
+            | Format::SigFigs(_)
+            | Format::Fixed { .. } => &[],
+            Format::Percent { .. } => &[("%", 1.0)],
+            Format::Bytes => &BYTE_UNITS,
+            Format::Count => &COUNT_UNITS,
+            Format::Duration { .. } => &[],
+        };
+        units
+            .iter()
+            .rev()
+            .find_map(|(unit, factor)| {
+                let number = cell.strip_suffix(unit).filter(|_| !unit.is_empty())?;
+                locale.parse(number).map(|n| n * factor)
+            })
+            .or_else(|| locale.parse(cell))
+    }
+}
+
+const BYTE_UNITS: [(&str, f64); 7] = [
+    ("B", 1.0),
+    ("KiB", 1024.0),
+    ("MiB", 1048576.0),
+    ("GiB", 1073741824.0),
+    ("TiB", 1099511627776.0),
+    ("PiB", 1125899906842624.0),
+    ("EiB", 1152921504606846976.0),
+];
+
+const COUNT_UNITS: [(&str, f64); 5] = [("", 1.0), ("K", 1e3), ("M", 1e6), ("B", 1e9), ("T", 1e12)];
+
+/// Scales `value` to the largest unit it reaches, with one decimal once a
+/// unit applies.
+fn scale(value: f64, units: &[(&str, f64)], locale: Locale) -> String {
+    let decimals = |factor: f64| usize::from(factor != 1.0);
+    let rounded = |factor: f64| {
+        let text = format!("{:.*}", decimals(factor), value / factor);
+        text.parse().unwrap_or(value / factor).abs() * factor
+    };
+    let mut index = units
+        .iter()
+        .rposition(|(_, factor)| value.abs() >= *factor)
+        .unwrap_or(0);
+    // Rounding can carry into the next unit: 999,950 is 1.0M, not 1000.0K.
+    while units
+        .get(index + 1)
+        .is_some_and(|(_, next)| rounded(units[index].1) >= *next)
+    {
+        index += 1;
+    }
+    let (unit, factor) = units[index];
+    let separator = if units[0].0.is_empty() { "" } else { " " };
+    format!(
+        "{}{}{}",
+        locale.format(value / factor, decimals(factor)),
+        separator,
+        unit
+    )
+}
+
+/// `{:e}` with a signed exponent of at least two digits, so values line up.
+fn scientific(value: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, value);
+    let Some((mantissa, exponent)) = text.split_once('e') else {
+        return text;
+    };
+    let (sign, digits) = match exponent.strip_prefix('-') {
+        Some(digits) => ('-', digits),
+        None => ('+', exponent),
+    };
+    format!("{}e{}{:0>2}", mantissa, sign, digits)
+}
+
+fn significant(value: f64, digits: usize) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return value.to_string();
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    if magnitude < -4 || magnitude >= digits as i32 {
+        return scientific(value, digits - 1);
+    }
+    let decimals = (digits as i32 - 1 - magnitude).max(0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+/// Formats seconds as `2h 13m 05s`, `1d 00h 00m 07s` or `350ms`.
+pub(crate) fn humanize_duration(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    if seconds < 1.0 && seconds > 0.0 {
+        return format!("{}{}ms", sign, (seconds * 1000.0).round());
+    }
+    let total = seconds.round() as u64;
+    let parts = [
+        (total / 86400, "d"),
+        (total / 3600 % 24, "h"),
+        (total / 60 % 60, "m"),
+        (total % 60, "s"),
+    ];
+    let first = parts.iter().position(|(n, _)| *n > 0).unwrap_or(3);
+    let text: Vec<String> = parts[first..]
+        .iter()
+        .enumerate()
+        .map(|(i, (n, unit))| {
+            if i == 0 {
+                format!("{}{}", n, unit)
+            } else {
+                format!("{:02}{}", n, unit)
+            }
+        })
+        .collect();
+    format!("{}{}", sign, text.join(" "))
+}
+
+/// Reads `2h 13m 05s`, `350ms` or ISO 8601 `P1DT2H13M5.5S` as seconds.
+fn parse_duration(text: &str) -> Option<f64> {
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, text),
+    };
+    let iso = text.strip_prefix('P');
+    let body = iso.unwrap_or(text);
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    let mut time = iso.is_none();
+    let mut chars = body.chars().peekable();
+    let mut any = false;
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == 'T' && iso.is_some() {
+            time = true;
+            continue;
+        }
+        let value: f64 = number.parse().ok()?;
+        number.clear();
+        let unit = c.to_ascii_lowercase();
+        let factor = match unit {
+            'w' => 604800.0,
+            'd' => 86400.0,
+            'h' => 3600.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                0.001
+            }
+            'm' if time => 60.0,
+            's' => 1.0,
+            _ => return None,
+        };
+        seconds += value * factor;
+        any = true;
+    }
+    (any && number.is_empty()).then_some(sign * seconds)
+}
+
+/// Formats a byte count in binary units, e.g. `1.5 KiB`.
+pub fn humanize_bytes(bytes: u64) -> String {
+    scale(bytes as f64, &BYTE_UNITS, Locale::default())
+}
+
+/// Formats a count with a K/M/B/T suffix, e.g. `1.2M`.
+pub fn humanize_count(count: i64) -> String {
+    scale(count as f64, &COUNT_UNITS, Locale::default())
+}
+
+fn draw_bar(fraction: f64, width: usize, charset: Charset) -> String {
+    let eighths = (fraction.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let (full, rest) = (eighths / 8, eighths % 8);
+    let mut bar = String::with_capacity(width * 3);
+    match charset {
+        Charset::Unicode => {
+            bar.extend(std::iter::repeat_n('█', full));
+            if rest > 0 {
+                bar.push(EIGHTHS[rest]);
+            }
+        }
+        Charset::Ascii => bar.extend(std::iter::repeat_n('#', (eighths + 4) / 8)),
+    }
+    let drawn = display_width(&bar);
+    bar.extend(std::iter::repeat_n(' ', width.saturating_sub(drawn)));
+    bar
+}
+
+impl Column {
+    pub fn format(&mut self, format: Format) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Appends `unit` to every displayed value, as in `12 ms`. The cells
+    /// stay bare numbers for sorting, aggregates and exports.
+    pub fn unit(&mut self, unit: &str) -> &mut Self {
+        self.unit = Some(unit.to_string());
+        self.unit_in_header = false;
+        self
+    }
+
+    /// Shows `unit` once in the header, as in `Latency (ms)`, instead of
+    /// after every value.
+    pub fn unit_in_header(&mut self, unit: &str) -> &mut Self {
+        self.unit = Some(unit.to_string());
+        self.unit_in_header = true;
+        self
+    }
+
+    /// The header as displayed, with the unit when it goes there.
+    pub(crate) fn title(&self) -> Cow<'_, str> {
+        match &self.unit {
+            Some(unit) if self.unit_in_header => Cow::Owned(format!("{} ({})", self.header, unit)),
+            _ => Cow::Borrowed(&self.header),
+        }
+    }
+
+    /// `cell` formatted for display in a column `width` wide, or at its
+    /// natural width when `None`. Numbers too wide for the column lose
+    /// precision rather than digits.
+    pub(crate) fn display<'a>(
+        &self,
+        cell: &'a str,
+        width: Option<usize>,
+        charset: Charset,
+        locale: Locale,
+    ) -> Cow<'a, str> {
+        let (room, unit) = match self.unit.as_deref() {
+            Some(unit) if !self.unit_in_header && !cell.trim().is_empty() => (
+                width.map(|w| w.saturating_sub(display_width(unit) + 1)),
+                Some(unit),
+            ),
+            _ => (width, None),
+        };
+        let mut text = self.format.apply(cell, room, charset, locale);
+        if let Some(room) = room.filter(|&room| display_width(&text) > room) {
+            if let Some(shorter) = self.format.shrink(&text, room, locale) {
+                text = Cow::Owned(shorter);
+            }
+        }
+        match unit {
+            Some(unit) => Cow::Owned(format!("{} {}", text, unit)),
+            None => text,
+        }
+    }
+}
+
+impl Table {
+    /// Sorts rows by the number in `column`, read through the column's
+    /// format, so humanized cells like `2 MiB` and `900 KiB` sort by size.
+    /// Cells that are not numbers go last.
+    pub fn sort_by_value(&mut self, column: impl Into<ColumnRef>) -> Result<(), TableError> {
+        let index = self.column_index(column)?;
+        let format = self.columns[index].format;
+        let locale = self.locale_of(index);
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            match (
+                format.value(&self.rows[a][index], locale),
+                format.value(&self.rows[b][index], locale),
+            ) {
+                (Some(x), Some(y)) => x.total_cmp(&y),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        });
+        self.reorder_rows(&order);
+        Ok(())
+    }
+
+    /// A row's cells as displayed, wrapped to `widths`.
+    pub(crate) fn row_lines(
+        &self,
+        widths: &[usize],
+        row: &[impl AsRef<str>],
+        charset: Charset,
+    ) -> Vec<Vec<String>> {
+        let cells: Vec<Cow<str>> = row
+            .iter()
+            .zip(&self.columns)
+            .zip(widths)
+            .enumerate()
+            .map(|(i, ((cell, column), &width))| {
+                if column.raw {
+                    return Cow::Borrowed(cell.as_ref());
+                }
+                column.display(cell.as_ref(), Some(width), charset, self.locale_of(i))
+            })
+            .collect();
+        self.cell_lines(widths, &cells, self.max_row_height, charset)
+    }
+}
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/highlight.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::ops::Range;
+
+use crate::{Color, ColorSpec, Column, ColumnRef, Locale, Table, TableError};
+
+impl Column {
+    /// Colors the parts of each cell matched by `pattern`. When the pattern
+    /// has capture groups only the captured text is colored.
+    #[cfg(feature = "regex")]
+    pub fn highlight(&mut self, pattern: regex::Regex, color: ColorSpec) -> &mut Self {
+        self.highlights.push((pattern, color));
+        self
+    }
+
+    /// Colors negative numbers red, positive ones green and zeros dim.
+    /// Regex highlights take precedence.
+    pub fn signed_colors(&mut self, enabled: bool) -> &mut Self {
+        self.signed_colors = enabled;
+        self
+    }
+
+    /// Byte ranges of `text` to draw in a color of their own, in order and
+    /// without overlaps. Signs are read with `locale`.
+    pub(crate) fn spans(&self, text: &str, locale: Locale) -> Vec<(Range<usize>, ColorSpec)> {
+        let mut spans: Vec<(Range<usize>, ColorSpec)> = Vec::new();
+        #[cfg(feature = "regex")]
+        for (pattern, color) in &self.highlights {
+            spans.extend(match_spans(pattern, text).map(|span| (span, color.clone())));
+        }
+        if spans.is_empty() && self.signed_colors {
+            spans.extend(sign_color(text, locale).map(|color| (0..text.len(), color)));
+        }
+        spans.sort_by_key(|(span, _)| span.start);
+        let mut end = 0;
+        spans.retain(|(span, _)| {
+            let keep = span.start >= end && !span.is_empty();
+            if keep {
+                end = span.end;
+            }
+            keep
+        });
+        spans
+    }
+}
+
+impl Table {
+    /// Draws the whole row at `row` in `spec`. The highlight stays with the
+    /// row when rows are sorted or removed.
+    pub fn highlight_row(&mut self, row: usize, spec: ColorSpec) -> Result<(), TableError> {
+        let row = self.row_index(row)?;
+        self.row_highlights.insert(row, spec);
+        Ok(())
+    }
+
+    /// Draws a single cell in `spec`, on top of any row highlight.
+    pub fn highlight_cell(
+        &mut self,
+        row: usize,
+        column: impl Into<ColumnRef>,
+        spec: ColorSpec,
+    ) -> Result<(), TableError> {
+        let row = self.row_index(row)?;
+        let column = self.column_index(column)?;
+        self.cell_highlights.insert((row, column), spec);
+        Ok(())
+    }
+
+    pub fn clear_highlights(&mut self) {
+        self.row_highlights.clear();
+        self.cell_highlights.clear();
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Table {
+    /// Keeps the rows whose cell in `column` matches `pattern`.
+    pub fn filter_regex(
+        &self,
+        column: impl Into<ColumnRef>,
+        pattern: &regex::Regex,
+    ) -> Result<Table, TableError> {
+        let column = self.column_index(column)?;
+        let indices: Vec<usize> = (0..self.rows.len())
+            .filter(|&i| pattern.is_match(&self.rows[i][column]))
+            .collect();
+        Ok(self.select_rows(indices))
+    }
+}
+
+fn sign_color(text: &str, locale: Locale) -> Option<ColorSpec> {
+    let value = locale.parse(text.trim().trim_end_matches('%'))?;
+    let mut spec = ColorSpec::new();
+    if value < 0.0 {
+        spec.set_fg(Some(Color::Red));
+    } else if value > 0.0 {
+        spec.set_fg(Some(Color::Green));
+    } else {
+        spec.set_dimmed(true);
+    }
+    Some(spec)
+}
+
+/// The spans of every match, or of its capture groups when it has any.
+#[cfg(feature = "regex")]
+pub(crate) fn match_spans<'a>(
+    pattern: &'a regex::Regex,
+    text: &'a str,
+) -> impl Iterator<Item = Range<usize>> + 'a {
+    pattern.captures_iter(text).flat_map(|captures| {
+        let groups = captures.len();
+        let first = if groups > 1 { 1 } else { 0 };
+        (first..groups)
+            .filter_map(|group| captures.get(group).map(|m| m.range()))
+            .collect::<Vec<_>>()
+    })
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/html.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::{Alignment, ColumnRef, Table, TableError};
+
+/// Extras a cell carries into HTML output only.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CellMeta {
+    tooltip: Option<String>,
+    data: Vec<(String, String)>,
+}
+
+impl Table {
+    /// Shown on hover in HTML output, as the cell's `title` attribute.
+    pub fn set_cell_tooltip(
+        &mut self,
+        row: usize,
+        column: impl Into<ColumnRef>,
+        text: &str,
+    ) -> Result<(), TableError> {
+        self.cell_meta_mut(row, column)?.tooltip = Some(text.to_string());
+        Ok(())
+    }
+
+    /// Adds a `data-<key>` attribute to the cell in HTML output.
+    pub fn set_cell_data(
+        &mut self,
+        row: usize,
+        column: impl Into<ColumnRef>,
+        key: &str,
+        value: &str,
+    ) -> Result<(), TableError> {
+        let data = &mut self.cell_meta_mut(row, column)?.data;
+        data.retain(|(existing, _)| existing != key);
+        data.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    fn cell_meta_mut(
+        &mut self,
+        row: usize,
+        column: impl Into<ColumnRef>,
+    ) -> Result<&mut CellMeta, TableError> {
+        let row = self.row_index(row)?;
+        let column = self.column_index(column)?;
+        Ok(self.cell_meta.entry((row, column)).or_default())
+    }
+
+    /// An HTML `<table>` with the title as caption and footers in `<tfoot>`.
+    /// Line breaks in cells become `<br>`.
+    pub fn to_html(&self) -> String {
+        let mut out = String::from("<table>\n");
+        if let Some(title) = &self.title {
+            out.push_str(&format!("<caption>{}</caption>\n", escape(title)));
+        }
+        out.push_str("<thead>\n<tr>");
+        for column in &self.columns {
+            out.push_str("<th");
+            if let Some(description) = &column.description {
+                out.push_str(&format!(" title=\"{}\"", escape(description)));
+            }
+            out.push_str(&format!(">{}</th>", cell_text(&column.header)));
+        }
+        out.push_str("</tr>\n</thead>\n<tbody>\n");
+        for (r, row) in self.rows.iter().enumerate() {
+            out.push_str("<tr>");
+            for (c, cell) in row.iter().enumerate() {
+                out.push_str("<td");
+                out.push_str(&self.alignment_attribute(c));
+                if let Some(meta) = self.cell_meta.get(&(r, c)) {
+                    if let Some(tooltip) = &meta.tooltip {
+                        out.push_str(&format!(" title=\"{}\"", escape(tooltip)));
+                    }
+                    for (key, value) in &meta.data {
+                        out.push_str(&format!(" data-{}=\"{}\"", data_key(key), escape(value)));
+                    }
+                }
+                out.push_str(&format!(">{}</td>", cell_text(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</tbody>\n");
+        let footers = self.footer_rows();
+        if !footers.is_empty() {
+            out.push_str("<tfoot>\n");
+            for footer in &footers {
+                out.push_str("<tr>");
+                for (c, cell) in footer.iter().enumerate() {
+                    let alignment = self.alignment_attribute(c);
+                    out.push_str(&format!("<td{}>{}</td>", alignment, cell_text(cell)));
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str("</tfoot>\n");
+        }
+        out.push_str("</table>\n");
+        out
+    }
+
+    fn alignment_attribute(&self, column: usize) -> String {
+        match self.columns[column].alignment {
+            Alignment::Left => String::new(),
+            Alignment::Center => " style=\"text-align: center\"".to_string(),
+            Alignment::Right => " style=\"text-align: right\"".to_string(),
+        }
+    }
+}
+
+fn cell_text(text: &str) -> String {
+    escape(text).replace('\n', "<br>")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Attribute names allow far less than values; anything else becomes `-`.
+fn data_key(key: &str) -> String {
+    key.chars()
+        .map(|c| match c {
+            'A'..='Z' => c.to_ascii_lowercase(),
+            'a'..='z' | '0'..='9' | '-' | '_' | '.' => c,
+            _ => '-',
+        })
+        .collect()
+}
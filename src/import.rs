@@ -0,0 +1,386 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/import.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::aggregate::parse_number;
+use crate::error::csv_error;
+use crate::{Alignment, RowPolicy, Table, TableError, TableStyle};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Selection {
+    #[default]
+    All,
+    Indices(Vec<usize>),
+    Names(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvDialect {
+    pub delimiter: u8,
+    pub has_headers: bool,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect {
+            delimiter: b',',
+            has_headers: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsvImportOptions {
+    /// Delimiter and header settings; comma-separated with a header row when `None`.
+    pub dialect: Option<CsvDialect>,
+    /// Data records to skip after the header row.
+    pub skip_rows: usize,
+    /// Stop reading after this many records have been imported.
+    pub max_rows: Option<usize>,
+    pub columns: Selection,
+    /// Trim leading and trailing whitespace from headers and cells.
+    pub trim: bool,
+    /// Records with missing fields are padded with empty cells unless the
+    /// policy is `Exact`.
+    pub row_policy: RowPolicy,
+    /// Replace invalid UTF-8 with U+FFFD instead of failing; every repaired
+    /// header and cell is listed in `import_report()`.
+    pub lossy_utf8: bool,
+    /// Source encoding label such as "latin1", "windows-1252" or "shift_jis";
+    /// UTF-8 when `None`.
+    #[cfg(feature = "encoding")]
+    pub encoding: Option<String>,
+}
+
+/// The headers and cells whose invalid UTF-8 a lossy import replaced.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    headers: Vec<usize>,
+    cells: Vec<(usize, usize)>,
+}
+
+impl ImportReport {
+    /// Columns whose header was repaired.
+    pub fn repaired_headers(&self) -> &[usize] {
+        &self.headers
+    }
+
+    /// Repaired cells as `(row, column)`, in the order they were read.
+    pub fn repaired_cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// Headers and cells repaired in total.
+    pub fn repaired(&self) -> usize {
+        self.headers.len() + self.cells.len()
+    }
+}
+
+impl Table {
+    /// What the CSV import that built this table repaired; empty for tables
+    /// built any other way. Positions are those at import time.
+    pub fn import_report(&self) -> &ImportReport {
+        &self.import_report
+    }
+
+    /// Gzip and zstd compressed files are decompressed transparently when the
+    /// `gzip` or `zstd` feature is enabled.
+    pub fn from_csv_with_options(
+        path: &str,
+        options: &CsvImportOptions,
+    ) -> Result<Self, TableError> {
+        Ok(Table::from_compressed_reader(File::open(path)?, options)?)
+    }
+
+    pub fn from_compressed_reader<R: Read>(
+        reader: R,
+        options: &CsvImportOptions,
+    ) -> io::Result<Self> {
+        Table::from_csv_reader(decompress(reader)?, options)
+    }
+
+    pub fn from_csv_reader<R: Read>(reader: R, options: &CsvImportOptions) -> io::Result<Self> {
+        #[cfg(feature = "encoding")]
+        let reader = transcode(reader, options.encoding.as_deref())?;
+        let dialect = options.dialect.unwrap_or_default();
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(dialect.delimiter)
+            .has_headers(dialect.has_headers)
+            .trim(if options.trim {
+                csv::Trim::All
+            } else {
+                csv::Trim::None
+            })
+            .flexible(options.row_policy != RowPolicy::Exact)
+            .from_reader(reader);
+        let mut report = ImportReport::default();
+        let mut headers = if options.lossy_utf8 {
+            let headers = reader.byte_headers().map_err(csv_error)?;
+            let mut repaired = Vec::new();
+            let headers = headers
+                .iter()
+                .enumerate()
+                .map(|(index, header)| {
+                    if std::str::from_utf8(header).is_err() {
+                        repaired.push(index);
+                    }
+                    String::from_utf8_lossy(header)
+                })
+                .collect();
+            if dialect.has_headers {
+                report.headers = repaired;
+            }
+            headers
+        } else {
+            reader.headers().map_err(csv_error)?.clone()
+        };
+        if !dialect.has_headers {
+            headers = (1..=headers.len())
+                .map(|i| format!("Column {}", i))
+                .collect();
+        }
+        let selected = select_columns(&headers, &options.columns)?;
+        report.headers = (0..selected.len())
+            .filter(|&column| report.headers.contains(&selected[column]))
+            .collect();
+
+        let mut table = Table::new(TableStyle::Simple);
+        table.set_row_policy(options.row_policy);
+        for &index in &selected {
+            table.add_column(&headers[index], 10, Alignment::Left);
+        }
+        let limit = options.max_rows.unwrap_or(usize::MAX);
+        for result in reader.byte_records().skip(options.skip_rows).take(limit) {
+            let record = result.map_err(csv_error)?;
+            let mut repaired = Vec::new();
+            let mut row = Vec::with_capacity(selected.len());
+            for (column, &index) in selected.iter().enumerate() {
+                let bytes = record.get(index).unwrap_or_default();
+                match std::str::from_utf8(bytes) {
+                    Ok(cell) => row.push(cell.to_string()),
+                    Err(error) if !options.lossy_utf8 => {
+                        return Err(invalid_utf8(&record, index, error));
+                    }
+                    Err(_) => {
+                        repaired.push(column);
+                        row.push(String::from_utf8_lossy(bytes).into_owned());
+                    }
+                }
+            }
+            table.try_add_row(row)?;
+            let row = table.rows.len() - 1;
+            report
+                .cells
+                .extend(repaired.into_iter().map(|column| (row, column)));
+        }
+        table.import_report = report;
+        Ok(table)
+    }
+}
+
+fn invalid_utf8(record: &csv::ByteRecord, field: usize, error: std::str::Utf8Error) -> io::Error {
+    let position = record
+        .position()
+        .cloned()
+        .unwrap_or_else(csv::Position::new);
+    TableError::Csv {
+        record: position.record() + 1,
+        line: position.line(),
+        byte: position.byte(),
+        field: Some(field),
+        message: format!("invalid UTF-8 after {} valid bytes", error.valid_up_to()),
+    }
+    .into()
+}
+
+#[cfg(feature = "encoding")]
+fn transcode<'a, R: Read + 'a>(reader: R, label: Option<&str>) -> io::Result<Box<dyn Read + 'a>> {
+    let Some(label) = label else {
+        return Ok(Box::new(reader));
+    };
+    let encoding = encoding_rs::Encoding::for_label(label.as_bytes())
+        .or_else(|| encoding_rs::Encoding::for_label(label.replace('-', "").as_bytes()))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown encoding label {:?}", label),
+            )
+        })?;
+    Ok(Box::new(
+        encoding_rs_io::DecodeReaderBytesBuilder::new()
+            .encoding(Some(encoding))
+            .build(reader),
+    ))
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+fn decompress<'a, R: Read + 'a>(reader: R) -> io::Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let magic = reader.fill_buf()?;
+    if magic.starts_with(&GZIP_MAGIC) {
+        #[cfg(feature = "gzip")]
+        return Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader)));
+        #[cfg(not(feature = "gzip"))]
+        return Err(unsupported_compression("gzip"));
+    }
+    if magic.starts_with(&ZSTD_MAGIC) {
+        #[cfg(feature = "zstd")]
+        return Ok(Box::new(zstd::stream::read::Decoder::with_buffer(reader)?));
+        #[cfg(not(feature = "zstd"))]
+        return Err(unsupported_compression("zstd"));
+    }
+    Ok(Box::new(reader))
+}
+
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn unsupported_compression(format: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{} input requires the `{}` feature", format, format),
+    )
+}
+
+const SNIFF_BYTES: u64 = 8 * 1024;
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+impl Table {
+    /// Guesses the dialect from the first few KB of `reader` and imports with
+    /// it, ignoring `options.dialect`. The detected dialect is returned too.
+    pub fn from_csv_reader_sniffed<R: Read>(
+        mut reader: R,
+        options: &CsvImportOptions,
+    ) -> io::Result<(Self, CsvDialect)> {
+        let mut sample = Vec::new();
+        reader.by_ref().take(SNIFF_BYTES).read_to_end(&mut sample)?;
+        // A full sample may end inside a line; sniff only the whole ones.
+        let whole = match sample.iter().rposition(|&b| b == b'\n') {
+            Some(end) if sample.len() as u64 == SNIFF_BYTES => &sample[..=end],
+            _ => &sample[..],
+        };
+        let dialect = sniff_csv(whole);
+        let options = CsvImportOptions {
+            dialect: Some(dialect),
+            ..options.clone()
+        };
+        let table = Table::from_csv_reader(io::Cursor::new(sample).chain(reader), &options)?;
+        Ok((table, dialect))
+    }
+}
+
+/// Guesses delimiter and header row from `sample`. A last line without a
+/// line break counts like the others, so pass whole lines only.
+pub fn sniff_csv(sample: &[u8]) -> CsvDialect {
+    let text = String::from_utf8_lossy(sample);
+    let mut lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines.truncate(50);
+
+    let delimiter = DELIMITERS
+        .iter()
+        .filter_map(|&delimiter| {
+            let counts: Vec<usize> = lines.iter().map(|l| count_unquoted(l, delimiter)).collect();
+            let first = *counts.first()?;
+            if first == 0 {
+                return None;
+            }
+            let consistent = counts.iter().filter(|&&c| c == first).count();
+            Some(((consistent, first), delimiter))
+        })
+        .max_by_key(|(score, _)| *score)
+        .map_or(b',', |(_, delimiter)| delimiter);
+
+    CsvDialect {
+        delimiter,
+        has_headers: detect_header(&lines, delimiter),
+    }
+}
+
+fn count_unquoted(line: &str, delimiter: u8) -> usize {
+    let mut quoted = false;
+    line.bytes()
+        .filter(|&b| {
+            if b == b'"' {
+                quoted = !quoted;
+            }
+            !quoted && b == delimiter
+        })
+        .count()
+}
+
+fn detect_header(lines: &[&str], delimiter: u8) -> bool {
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|line| {
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(delimiter)
+                .has_headers(false)
+                .from_reader(line.as_bytes());
+            reader
+                .records()
+                .next()
+                .and_then(Result::ok)
+                .map(|r| r.iter().map(|c| c.trim().to_string()).collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    let Some((first, data)) = rows.split_first() else {
+        return true;
+    };
+    if data.is_empty() {
+        return true;
+    }
+
+    let mut votes = 0i32;
+    for (column, header) in first.iter().enumerate() {
+        let values: Vec<&String> = data.iter().filter_map(|row| row.get(column)).collect();
+        let numeric = values.iter().filter(|v| parse_number(v).is_some()).count();
+        if numeric * 2 > values.len() {
+            votes += if parse_number(header).is_some() {
+                -1
+            } else {
+                1
+            };
+        }
+    }
+    if votes != 0 {
+        return votes > 0;
+    }
+    let mut distinct = first.clone();
+    distinct.sort();
+    distinct.dedup();
+    distinct.len() == first.len() && first.iter().all(|h| !h.is_empty())
+}
+
+fn select_columns(
+    headers: &csv::StringRecord,
+    selection: &Selection,
+) -> Result<Vec<usize>, TableError> {
+    match selection {
+        Selection::All => Ok((0..headers.len()).collect()),
+        Selection::Indices(indices) => indices
+            .iter()
+            .map(|&index| {
+                if index < headers.len() {
+                    Ok(index)
+                } else {
+                    Err(TableError::ColumnOutOfRange(index))
+                }
+            })
+            .collect(),
+        Selection::Names(names) => names
+            .iter()
+            .map(|name| {
+                headers
+                    .iter()
+                    .position(|header| header == name)
+                    .ok_or_else(|| TableError::ColumnNotFound(name.clone()))
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/importer.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+
+#[cfg(feature = "csv")]
+use crate::CsvImportOptions;
+use crate::Table;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Settings of the `csv` and `tsv` formats.
+    #[cfg(feature = "csv")]
+    pub csv: CsvImportOptions,
+    /// Settings of custom formats, by name.
+    pub extra: BTreeMap<String, String>,
+}
+
+/// An input format. Closures taking the reader and options are importers
+/// too.
+pub trait Importer: Send + Sync {
+    fn import(&self, reader: &mut dyn Read, options: &ImportOptions) -> io::Result<Table>;
+}
+
+impl<F> Importer for F
+where
+    F: Fn(&mut dyn Read, &ImportOptions) -> io::Result<Table> + Send + Sync,
+{
+    fn import(&self, reader: &mut dyn Read, options: &ImportOptions) -> io::Result<Table> {
+        self(reader, options)
+    }
+}
+
+/// Importers by name, the counterpart of `Exporters`.
+pub struct Importers {
+    importers: BTreeMap<String, Box<dyn Importer>>,
+}
+
+impl Importers {
+    /// The built-in formats: `csv` and `tsv` with the `csv` feature, `json`
+    /// with the `json` feature.
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut importers = Importers::empty();
+        #[cfg(feature = "csv")]
+        importers.register("csv", |reader: &mut dyn Read, options: &ImportOptions| {
+            Table::from_compressed_reader(reader, &options.csv)
+        });
+        #[cfg(feature = "csv")]
+        importers.register("tsv", |reader: &mut dyn Read, options: &ImportOptions| {
+            let mut csv = options.csv.clone();
+            let dialect = csv.dialect.get_or_insert_with(Default::default);
+            dialect.delimiter = b'\t';
+            Table::from_compressed_reader(reader, &csv)
+        });
+        #[cfg(feature = "json")]
+        importers.register("json", |reader: &mut dyn Read, _: &ImportOptions| {
+            Table::from_json_reader(reader)
+        });
+        importers
+    }
+
+    pub fn empty() -> Self {
+        Importers {
+            importers: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a format, replacing any registered under the same name.
+    pub fn register(&mut self, name: &str, importer: impl Importer + 'static) {
+        self.importers.insert(name.to_string(), Box::new(importer));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Importer> {
+        self.importers.get(name).map(|importer| &**importer)
+    }
+
+    /// Registered names in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.importers.keys().map(String::as_str)
+    }
+
+    pub fn import(
+        &self,
+        name: &str,
+        reader: &mut dyn Read,
+        options: &ImportOptions,
+    ) -> io::Result<Table> {
+        match self.get(name) {
+            Some(importer) => importer.import(reader, options),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "unknown format `{}`, expected one of: {}",
+                    name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                ),
+            )),
+        }
+    }
+}
+
+impl Default for Importers {
+    fn default() -> Self {
+        Importers::new()
+    }
+}
+
+impl Table {
+    /// Reads a table in one of the built-in formats of `Importers::new`.
+    /// Custom formats are read through an `Importers` they are registered
+    /// with.
+    pub fn import<R: Read>(name: &str, mut reader: R, options: &ImportOptions) -> io::Result<Self> {
+        Importers::new().import(name, &mut reader, options)
+    }
+}
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/index.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::text::display_width;
+use crate::view::remap_cells;
+use crate::{Alignment, Column, Table};
+
+/// How the generated index column numbers rows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndexFormat {
+    /// `1`, `2`, `3`
+    #[default]
+    Number,
+    /// Numbers padded with zeros to `width` digits: `007`.
+    ZeroPadded { width: usize },
+    /// `a` to `z`, then `aa`, `ab` and so on.
+    Alphabetic,
+    /// `I`, `II`, `III`; zero and numbers above 3999 fall back to digits.
+    Roman,
+}
+
+impl IndexFormat {
+    pub fn label(self, number: usize) -> String {
+        match self {
+            IndexFormat::Number => number.to_string(),
+            IndexFormat::ZeroPadded { width } => format!("{:0>1$}", number, width),
+            IndexFormat::Alphabetic => alphabetic(number),
+            IndexFormat::Roman => roman(number),
+        }
+    }
+}
+
+/// Bijective base 26, so 1 is `a`, 26 is `z` and 27 is `aa`.
+fn alphabetic(mut number: usize) -> String {
+    let mut letters = Vec::new();
+    while number > 0 {
+        number -= 1;
+        letters.push(b'a' + (number % 26) as u8);
+        number /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).expect("letters are ASCII")
+}
+
+fn roman(mut number: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    if !(1..4000).contains(&number) {
+        return number.to_string();
+    }
+    let mut text = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            text.push_str(numeral);
+            number -= value;
+        }
+    }
+    text
+}
+
+/// Settings of the generated index column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Index {
+    header: String,
+    format: IndexFormat,
+    pub(crate) start: usize,
+}
+
+impl Table {
+    /// Adds a generated first column numbering the rows, headed `#`. It is
+    /// only drawn; rows, sorting and exports do not see it.
+    pub fn show_index(&mut self, format: IndexFormat) {
+        let start = self.index.as_ref().map_or(1, |index| index.start);
+        self.index = Some(Index {
+            header: "#".to_string(),
+            format,
+            start,
+        });
+    }
+
+    pub fn hide_index(&mut self) {
+        self.index = None;
+    }
+
+    /// Number given to the first row, 1 by default. A page of a longer
+    /// listing starts where the previous page ended, so references stay
+    /// valid across pages.
+    pub fn set_index_start(&mut self, start: usize) {
+        if let Some(index) = &mut self.index {
+            index.start = start;
+        }
+    }
+
+    pub fn set_index_header(&mut self, header: &str) {
+        if let Some(index) = &mut self.index {
+            index.header = header.to_string();
+        }
+    }
+
+    /// A copy with the index as a real first column.
+    pub(crate) fn with_index(&self, index: &Index) -> Table {
+        let all: Vec<usize> = (0..self.columns.len()).collect();
+        let mut table = self.select_columns(&all);
+        table.index = None;
+        table
+            .columns
+            .insert(0, Column::new(&index.header, 0, Alignment::Right));
+        for (i, row) in table.rows.iter_mut().enumerate() {
+            row.insert(0, index.format.label(index.start + i));
+        }
+        if let Some(widths) = &mut table.locked_widths {
+            let labels = table.rows.iter().map(|row| display_width(&row[0]));
+            widths.insert(0, labels.fold(display_width(&index.header), usize::max));
+        }
+        if let Some(total) = &mut table.grand_total {
+            total.insert(0, String::new());
+        }
+        for (column, _) in &mut table.footers {
+            *column += 1;
+        }
+        table.cell_highlights = remap_cells(&table.cell_highlights, |row, column| {
+            Some((row, column + 1))
+        });
+        table.cell_meta = remap_cells(&table.cell_meta, |row, column| Some((row, column + 1)));
+        table
+    }
+}
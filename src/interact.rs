@@ -0,0 +1,606 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/interact.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::cmp::{Ordering, Reverse};
+use std::io::{self, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, SetAttribute};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use unicode_width::UnicodeWidthChar;
+
+use crate::render::{bottom_lines, LineSink};
+use crate::text::display_width;
+use crate::{Alignment, Locale, Table, VerticalAlignment};
+
+impl Table {
+    /// Shows the table full-screen until the user quits, and returns the
+    /// rows as they were last sorted and filtered.
+    ///
+    /// Keys: arrows, `h`/`j`/`k`/`l`, PageUp/PageDown, Home/End scroll, with
+    /// pinned columns staying in view; `g`/`G` go to the first and last page
+    /// and `:37` Enter to page 37; Tab picks the next sort column and `r`
+    /// reverses it; `/` starts a live filter that Enter keeps and Esc
+    /// discards; `?` lists the keys and column descriptions; `q` or Esc
+    /// quits.
+    pub fn interact(&self) -> io::Result<Table> {
+        let mut session = Session::new(self);
+        session.run()?;
+        Ok(session.view_table())
+    }
+
+    /// Lets the user pick a row with the arrow keys and Enter. Returns the
+    /// row's index in this table and its cells, or `None` if the user quit.
+    /// Sorting and filtering work as in [`Table::interact`].
+    pub fn select_row(&self) -> io::Result<Option<(usize, Vec<String>)>> {
+        let mut session = Session::picking(self);
+        Ok(match session.run()? {
+            Control::Select => session.selected(),
+            _ => None,
+        })
+    }
+}
+
+/// Restores the terminal on drop, also when drawing fails midway.
+struct Screen;
+
+impl Screen {
+    fn enter(stdout: &mut io::Stdout) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Control {
+    Continue,
+    Select,
+    Quit,
+}
+
+pub(crate) struct Session<'a> {
+    table: &'a Table,
+    /// Sort column and whether it is descending.
+    sort: Option<(usize, bool)>,
+    filter: String,
+    editing: Option<String>,
+    /// Page number being typed after `:`.
+    goto: Option<String>,
+    help: bool,
+    /// Indices into `table.rows`, in display order.
+    view: Vec<usize>,
+    pub(crate) offset: usize,
+    page: usize,
+    /// First unpinned column shown; pinned columns are always shown.
+    first_column: usize,
+    /// Position of the highlighted row in `view`, when picking a row.
+    cursor: Option<usize>,
+    matcher: Matcher,
+    /// Matched char indices per cell, indexed like `table.rows`; empty for
+    /// rows that are filtered out or when no filter is set.
+    pub(crate) hits: Vec<Vec<Option<Vec<usize>>>>,
+}
+
+impl<'a> Session<'a> {
+    pub(crate) fn new(table: &'a Table) -> Self {
+        let mut session = Session {
+            table,
+            sort: None,
+            filter: String::new(),
+            editing: None,
+            goto: None,
+            help: false,
+            view: Vec::new(),
+            offset: 0,
+            page: 1,
+            first_column: 0,
+            cursor: None,
+            matcher: Matcher::default(),
+            hits: Vec::new(),
+        };
+        session.refresh();
+        session
+    }
+
+    pub(crate) fn picking(table: &'a Table) -> Self {
+        let mut session = Session::new(table);
+        session.cursor = Some(0);
+        session
+    }
+
+    fn run(&mut self) -> io::Result<Control> {
+        let mut stdout = io::stdout();
+        let _screen = Screen::enter(&mut stdout)?;
+        loop {
+            let (width, height) = terminal::size()?;
+            self.resize(usize::from(height));
+            self.draw(&mut stdout, usize::from(width), usize::from(height))?;
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => match self.handle(key) {
+                    Control::Continue => {}
+                    control => return Ok(control),
+                },
+                // Resizes fall through to the next pass, which re-measures
+                // the screen and lays the table out again.
+                _ => {}
+            }
+        }
+    }
+
+    pub(crate) fn handle(&mut self, key: KeyEvent) -> Control {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Control::Quit;
+        }
+        if self.help {
+            self.help = false;
+            return Control::Continue;
+        }
+        if let Some(filter) = &mut self.editing {
+            match key.code {
+                KeyCode::Char(c) => filter.push(c),
+                KeyCode::Backspace => {
+                    filter.pop();
+                }
+                KeyCode::Enter => self.filter = self.editing.take().unwrap_or_default(),
+                KeyCode::Esc => self.editing = None,
+                _ => {}
+            }
+            self.refresh();
+            return Control::Continue;
+        }
+        if let Some(number) = &mut self.goto {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => number.push(c),
+                KeyCode::Backspace => {
+                    number.pop();
+                }
+                KeyCode::Enter => {
+                    if let Ok(page) = number.parse() {
+                        self.go_to_page(page);
+                    }
+                    self.goto = None;
+                }
+                KeyCode::Esc => self.goto = None,
+                _ => {}
+            }
+            return Control::Continue;
+        }
+        if let Some(cursor) = self.cursor {
+            let moved = match key.code {
+                KeyCode::Down | KeyCode::Char('j') => Some(cursor + 1),
+                KeyCode::Up | KeyCode::Char('k') => Some(cursor.saturating_sub(1)),
+                KeyCode::PageDown => Some(cursor + self.page),
+                KeyCode::PageUp => Some(cursor.saturating_sub(self.page)),
+                KeyCode::Home | KeyCode::Char('g') => Some(0),
+                KeyCode::End | KeyCode::Char('G') => Some(usize::MAX),
+                KeyCode::Enter if !self.view.is_empty() => return Control::Select,
+                _ => None,
+            };
+            if let Some(moved) = moved {
+                self.move_cursor(moved);
+                return Control::Continue;
+            }
+        }
+        let last = self.view.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Control::Quit,
+            KeyCode::Down | KeyCode::Char('j') => self.offset = (self.offset + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => self.offset = self.offset.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.offset = (self.offset + self.page).min(last)
+            }
+            KeyCode::PageUp => self.offset = self.offset.saturating_sub(self.page),
+            KeyCode::Right | KeyCode::Char('l') => {
+                let scrolling = self.table.columns.iter().filter(|c| !c.pinned).count();
+                self.first_column = (self.first_column + 1).min(scrolling.saturating_sub(1));
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.first_column = self.first_column.saturating_sub(1)
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.offset = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                self.offset = self.view.len().saturating_sub(self.page)
+            }
+            KeyCode::Tab => {
+                let next = self.sort.map_or(0, |(column, _)| column + 1);
+                self.sort = (next < self.table.columns.len()).then_some((next, false));
+                self.refresh();
+            }
+            KeyCode::Char('r') => {
+                if let Some((_, descending)) = &mut self.sort {
+                    *descending = !*descending;
+                    self.refresh();
+                }
+            }
+            KeyCode::Char('/') => self.editing = Some(self.filter.clone()),
+            KeyCode::Char(':') => self.goto = Some(String::new()),
+            KeyCode::Char('?') => self.help = true,
+            _ => {}
+        }
+        Control::Continue
+    }
+
+    pub(crate) fn selected(&self) -> Option<(usize, Vec<String>)> {
+        let index = *self.view.get(self.cursor?)?;
+        Some((index, self.table.rows[index].clone()))
+    }
+
+    /// Fits the page to a screen of `height` lines, keeping the cursor in
+    /// view when the screen shrank.
+    pub(crate) fn resize(&mut self, height: usize) {
+        self.page = height.saturating_sub(self.chrome_lines() + 1).max(1);
+        if let Some(cursor) = self.cursor {
+            self.move_cursor(cursor);
+        }
+    }
+
+    /// Scrolls to page `number`, counting from 1, with the last page
+    /// filling the screen. The cursor moves to the page's first row.
+    fn go_to_page(&mut self, number: usize) {
+        let last = self.view.len().saturating_sub(self.page);
+        self.offset = (number.saturating_sub(1) * self.page).min(last);
+        if self.cursor.is_some() {
+            self.cursor = Some(self.offset);
+        }
+    }
+
+    /// The page showing the last row on screen, and the number of pages.
+    pub(crate) fn page_position(&self) -> (usize, usize) {
+        let end = (self.offset + self.page).min(self.view.len());
+        (end.div_ceil(self.page), self.view.len().div_ceil(self.page))
+    }
+
+    fn move_cursor(&mut self, position: usize) {
+        let cursor = position.min(self.view.len().saturating_sub(1));
+        self.cursor = Some(cursor);
+        if cursor < self.offset {
+            self.offset = cursor;
+        } else if cursor >= self.offset + self.page {
+            self.offset = cursor + 1 - self.page;
+        }
+    }
+
+    /// The sorted and filtered rows as a table of their own.
+    pub(crate) fn view_table(&self) -> Table {
+        let mut view = self.table.select_rows(self.view.iter().copied());
+        view.max_rows = None;
+        view
+    }
+
+    fn active_filter(&self) -> &str {
+        self.editing.as_deref().unwrap_or(&self.filter)
+    }
+
+    fn refresh(&mut self) {
+        let needle = self.active_filter().to_string();
+        self.matcher.prepare(&needle);
+        let rows = &self.table.rows;
+        self.hits = vec![Vec::new(); rows.len()];
+        let mut scored = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            if needle.is_empty() {
+                scored.push((i, 0));
+                continue;
+            }
+            let hits: Vec<Option<(i64, Vec<usize>)>> = row
+                .iter()
+                .map(|cell| self.matcher.find(&needle, cell))
+                .collect();
+            let Some(score) = hits.iter().flatten().map(|(score, _)| *score).max() else {
+                continue;
+            };
+            self.hits[i] = hits.into_iter().map(|hit| hit.map(|(_, at)| at)).collect();
+            scored.push((i, score));
+        }
+        match self.sort {
+            Some((column, descending)) => {
+                let locale = self.table.locale_of(column);
+                scored.sort_by(|&(a, _), &(b, _)| {
+                    let ordering = compare_cells(&rows[a][column], &rows[b][column], locale);
+                    if descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+            }
+            None => scored.sort_by_key(|&(_, score)| Reverse(score)),
+        }
+        self.view = scored.into_iter().map(|(i, _)| i).collect();
+        self.offset = self.offset.min(self.view.len().saturating_sub(1));
+        if let Some(cursor) = self.cursor {
+            self.move_cursor(cursor);
+        }
+    }
+
+    fn chrome_lines(&self) -> usize {
+        let mut sink = LineSink::default();
+        let options = self.table.render_options();
+        self.table
+            .render_body(&mut sink, &options, &[], &[], 0, &[])
+            .expect("rendering into memory cannot fail");
+        sink.lines.len() + usize::from(self.table.title.is_some())
+    }
+
+    /// The help screen: keys, then each column with its description.
+    pub(crate) fn help_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            "Arrows, h j k l, PageUp, PageDown, Home, End  scroll".to_string(),
+            "g, G  first and last page, :37 Enter  page 37".to_string(),
+            "Tab  sort by the next column, r  reverse the sort".to_string(),
+            "/  filter, Enter keeps it, Esc discards it".to_string(),
+            "q, Esc  quit".to_string(),
+        ];
+        if self.cursor.is_some() {
+            lines.insert(4, "Enter  select the highlighted row".to_string());
+        }
+        let columns = &self.table.columns;
+        if columns.iter().any(|column| column.description.is_some()) {
+            lines.push(String::new());
+            let label = columns
+                .iter()
+                .map(|column| display_width(&column.header))
+                .max()
+                .unwrap_or(0);
+            for column in columns {
+                let description = column.description.as_deref().unwrap_or_default();
+                let fill = label - display_width(&column.header);
+                let line = format!("{}{}  {}", column.header, " ".repeat(fill), description);
+                lines.push(line.trim_end().to_string());
+            }
+        }
+        lines
+    }
+
+    fn draw(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        if self.help {
+            queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+            for line in self.help_lines().iter().take(height.saturating_sub(1)) {
+                write!(out, "{}\r\n", line)?;
+            }
+            write!(out, "Press any key to return")?;
+            return out.flush();
+        }
+        let view = self.view_table();
+        let columns = view.window_columns(self.first_column, width);
+        let view = view.select_columns(&columns);
+        let mut options = view.render_options();
+        options.max_width = Some(width);
+        let widths = view.resolve_widths(&options);
+        let end = (self.offset + self.page).min(view.rows.len());
+        let page = &view.rows[self.offset..end];
+
+        let mut sink = LineSink::default();
+        view.render_body(&mut sink, &options, &widths, &[], 0, &[])?;
+        let bottom = bottom_lines(options.style);
+        let mut line = sink.lines.len() - bottom;
+        let mut sink = LineSink::default();
+        view.render_body(&mut sink, &options, &widths, page, self.offset, &[])?;
+        let mut lines = sink.lines;
+        lines.truncate(height.saturating_sub(1));
+
+        // Work out which physical lines each row occupies, so the cursor row
+        // can be reversed and matched characters underlined.
+        let offsets = view.cell_offsets(&options, &widths);
+        let mut marks = vec![Vec::new(); lines.len()];
+        let mut reversed = 0..0;
+        for (k, row) in page.iter().enumerate() {
+            let cells = view.row_lines(&widths, row, options.charset);
+            let row_height = cells.iter().map(Vec::len).max().unwrap_or(1);
+            if self.cursor == Some(self.offset + k) {
+                reversed = line..line + row_height;
+            }
+            let hits = &self.hits[self.view[self.offset + k]];
+            for (c, original) in columns.iter().enumerate() {
+                let hit = hits.get(*original).and_then(Option::as_ref);
+                let (Some(at), [shown]) = (hit, cells[c].as_slice()) else {
+                    continue;
+                };
+                if *shown != row[c] {
+                    continue;
+                }
+                let column = &view.columns[c];
+                let fill = widths[c].saturating_sub(display_width(shown));
+                let start = offsets[c]
+                    + match column.alignment {
+                        Alignment::Left => 0,
+                        Alignment::Center => fill / 2,
+                        Alignment::Right => fill,
+                    };
+                let shift = match column.vertical_alignment {
+                    VerticalAlignment::Top => 0,
+                    VerticalAlignment::Middle => (row_height - 1) / 2,
+                    VerticalAlignment::Bottom => row_height - 1,
+                };
+                let Some(marked) = marks.get_mut(line + shift) else {
+                    continue;
+                };
+                let mut x = start;
+                for (i, ch) in shown.chars().enumerate() {
+                    if at.contains(&i) {
+                        marked.push(x);
+                    }
+                    x += ch.width().unwrap_or(0);
+                }
+            }
+            line += row_height;
+        }
+
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+        for (i, line) in lines.iter().enumerate() {
+            emit_line(out, line, &marks[i], reversed.contains(&i))?;
+        }
+        write!(out, "{}", self.status())?;
+        out.flush()
+    }
+
+    pub(crate) fn status(&self) -> String {
+        let sort = match self.sort {
+            Some((column, descending)) => format!(
+                "{} {}",
+                self.table.columns[column].header,
+                if descending { "desc" } else { "asc" }
+            ),
+            None => "none".to_string(),
+        };
+        let rows = if self.view.is_empty() {
+            "no rows".to_string()
+        } else {
+            let end = (self.offset + self.page).min(self.view.len());
+            let (page, pages) = self.page_position();
+            format!(
+                "rows {}-{} of {}, page {} of {}",
+                self.offset + 1,
+                end,
+                self.view.len(),
+                page,
+                pages
+            )
+        };
+        let keys = if self.cursor.is_some() {
+            "Enter select, Tab sort, r reverse, / filter, ? help, q quit"
+        } else {
+            "Tab sort, r reverse, / filter, ? help, q quit"
+        };
+        if let Some(number) = &self.goto {
+            return format!(":{}", number);
+        }
+        match &self.editing {
+            Some(filter) => format!("/{}  {}", filter, self.match_summary()),
+            None => format!(
+                "{} | sort: {} | filter: {} | {}",
+                rows, sort, self.filter, keys
+            ),
+        }
+    }
+
+    /// How many of the shown rows match in each column, e.g. "Name 3, City 1".
+    pub(crate) fn match_summary(&self) -> String {
+        let counts = self
+            .table
+            .columns
+            .iter()
+            .enumerate()
+            .filter_map(|(c, column)| {
+                let count = self
+                    .view
+                    .iter()
+                    .filter(|&&i| matches!(self.hits[i].get(c), Some(Some(_))))
+                    .count();
+                (count > 0).then(|| format!("{} {}", column.header, count))
+            });
+        counts.collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn emit_line(out: &mut impl Write, line: &str, marks: &[usize], reverse: bool) -> io::Result<()> {
+    if reverse {
+        queue!(out, SetAttribute(Attribute::Reverse))?;
+    }
+    let mut x = 0;
+    for ch in line.chars() {
+        let marked = marks.contains(&x);
+        if marked {
+            queue!(
+                out,
+                SetAttribute(Attribute::Bold),
+                SetAttribute(Attribute::Underlined)
+            )?;
+        }
+        write!(out, "{}", ch)?;
+        if marked {
+            queue!(
+                out,
+                SetAttribute(Attribute::NormalIntensity),
+                SetAttribute(Attribute::NoUnderline)
+            )?;
+        }
+        x += ch.width().unwrap_or(0);
+    }
+    if reverse {
+        queue!(out, SetAttribute(Attribute::Reset))?;
+    }
+    write!(out, "\r\n")
+}
+
+/// Finds a filter in a cell, returning a score for ranking rows and the
+/// char indices to highlight. With the `fuzzy` feature the filter's
+/// characters only need to appear in order; otherwise it has to occur as a
+/// case-insensitive substring. With the `regex` feature a filter starting
+/// with `/` is a pattern instead.
+#[derive(Default)]
+struct Matcher {
+    #[cfg(feature = "fuzzy")]
+    skim: fuzzy_matcher::skim::SkimMatcherV2,
+    #[cfg(feature = "regex")]
+    pattern: Option<regex::Regex>,
+}
+
+impl Matcher {
+    #[cfg(feature = "regex")]
+    fn prepare(&mut self, needle: &str) {
+        self.pattern = needle
+            .strip_prefix('/')
+            .and_then(|pattern| regex::Regex::new(pattern).ok());
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn prepare(&mut self, _: &str) {}
+
+    fn find(&self, needle: &str, cell: &str) -> Option<(i64, Vec<usize>)> {
+        #[cfg(feature = "regex")]
+        if needle.starts_with('/') {
+            let pattern = self.pattern.as_ref()?;
+            let spans: Vec<_> = crate::highlight::match_spans(pattern, cell).collect();
+            let at: Vec<usize> = cell
+                .char_indices()
+                .enumerate()
+                .filter(|(_, (byte, _))| spans.iter().any(|span| span.contains(byte)))
+                .map(|(i, _)| i)
+                .collect();
+            return pattern.is_match(cell).then_some((0, at));
+        }
+        self.find_text(needle, cell)
+    }
+
+    #[cfg(feature = "fuzzy")]
+    fn find_text(&self, needle: &str, cell: &str) -> Option<(i64, Vec<usize>)> {
+        use fuzzy_matcher::FuzzyMatcher;
+        self.skim.fuzzy_indices(cell, needle)
+    }
+
+    #[cfg(not(feature = "fuzzy"))]
+    fn find_text(&self, needle: &str, cell: &str) -> Option<(i64, Vec<usize>)> {
+        let hay: Vec<char> = cell.chars().collect();
+        let pattern: Vec<char> = needle.chars().collect();
+        let last = hay.len().checked_sub(pattern.len())?;
+        let start = (0..=last).find(|&start| {
+            pattern
+                .iter()
+                .zip(&hay[start..])
+                .all(|(p, h)| h.to_lowercase().eq(p.to_lowercase()))
+        })?;
+        Some((0, (start..start + pattern.len()).collect()))
+    }
+}
+
+/// Compares numerically when both cells are numbers in `locale`, textually
+/// otherwise.
+fn compare_cells(a: &str, b: &str, locale: Locale) -> Ordering {
+    match (locale.parse(a), locale.parse(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        _ => a.cmp(b),
+    }
+}
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/json.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::io::{self, Read};
+
+use serde_json::Value;
+
+use crate::{Alignment, Table, TableStyle};
+
+impl Table {
+    /// Reads a JSON array of objects (keys become columns, in order of first
+    /// appearance) or an array of arrays whose first element is the header row.
+    pub fn from_json_reader<R: Read>(reader: R) -> io::Result<Self> {
+        let value: Value = serde_json::from_reader(reader)?;
+        let Value::Array(items) = value else {
+            return Err(invalid("expected a JSON array"));
+        };
+
+        let mut table = Table::new(TableStyle::Simple);
+        match items.first() {
+            None => {}
+            Some(Value::Object(_)) => {
+                let mut headers: Vec<String> = Vec::new();
+                for item in &items {
+                    let Value::Object(object) = item else {
+                        return Err(invalid("expected every element to be an object"));
+                    };
+                    for key in object.keys() {
+                        if !headers.contains(key) {
+                            headers.push(key.clone());
+                        }
+                    }
+                }
+                for header in &headers {
+                    table.add_column(header, 10, Alignment::Left);
+                }
+                for item in &items {
+                    let row = headers.iter().map(|h| cell(item.get(h))).collect();
+                    table.try_add_row(row)?;
+                }
+            }
+            Some(Value::Array(header)) => {
+                for value in header {
+                    table.add_column(&cell(Some(value)), 10, Alignment::Left);
+                }
+                for item in &items[1..] {
+                    let Value::Array(values) = item else {
+                        return Err(invalid("expected every element to be an array"));
+                    };
+                    table.try_add_row(values.iter().map(|v| cell(Some(v))).collect())?;
+                }
+            }
+            Some(_) => return Err(invalid("expected an array of objects or arrays")),
+        }
+        Ok(table)
+    }
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,475 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/layout.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::cmp::Reverse;
+use std::io::{self, Write};
+
+use crate::render::{cell_padding, style_config, MeasureSink};
+use unicode_width::UnicodeWidthChar;
+
+use crate::text::{display_width, pad, visible_width};
+use crate::{Alignment, Charset, HeaderOrientation, RenderOptions, Table, TableStyle, Wrap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    Even,
+    Weighted,
+    ShrinkLongest,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// Content width of each column, excluding padding and borders.
+    pub column_widths: Vec<usize>,
+    /// Display width of the widest rendered line.
+    pub total_width: usize,
+    /// Number of rendered lines.
+    pub height: usize,
+}
+
+impl Layout {
+    /// Gives every table the same column widths: at each position the
+    /// widest content of any table. Meant for tables with the same columns,
+    /// such as per-group tables printed one below the other.
+    pub fn unify(tables: &mut [&mut Table]) {
+        let widths = shared_widths(tables.iter().map(|table| &**table));
+        for table in tables {
+            table.widen_columns(&widths);
+        }
+    }
+}
+
+impl Table {
+    pub fn set_total_width(&mut self, width: usize, distribution: Distribution) {
+        self.total_width = Some((width, distribution));
+    }
+
+    /// The pinned columns plus as many of the other columns as fit into
+    /// `width`, starting with the `first`-th unpinned column.
+    pub fn column_window(&self, first: usize, width: usize) -> Table {
+        self.select_columns(&self.window_columns(first, width))
+    }
+
+    /// When enabled, tables wider than the render options' `max_width` (see
+    /// there for the default) print as several tables of columns one after
+    /// another, each repeating the pinned columns.
+    pub fn set_column_paging(&mut self, paging: bool) {
+        self.column_paging = paging;
+    }
+
+    /// Splits the table into column windows that each fit into `width`.
+    pub fn column_pages(&self, width: usize) -> Vec<Table> {
+        let scrolling = self.columns.iter().filter(|c| !c.pinned).count();
+        let mut pages = Vec::new();
+        let mut first = 0;
+        loop {
+            let columns = self.window_columns(first, width);
+            let shown = columns.iter().filter(|&&i| !self.columns[i].pinned).count();
+            let mut page = self.select_columns(&columns);
+            page.column_paging = false;
+            pages.push(page);
+            first += shown.max(1);
+            if first >= scrolling {
+                return pages;
+            }
+        }
+    }
+
+    pub fn compute_layout(&self) -> Layout {
+        let mut sink = MeasureSink::default();
+        self.render(&mut sink)
+            .expect("measuring a table cannot fail");
+        Layout {
+            column_widths: self.resolve_widths(&self.render_options()),
+            total_width: sink.width,
+            height: sink.height,
+        }
+    }
+}
+
+/// The widest content at each column position across `tables`.
+pub(crate) fn shared_widths<'a>(tables: impl IntoIterator<Item = &'a Table>) -> Vec<usize> {
+    let mut shared: Vec<usize> = Vec::new();
+    for table in tables {
+        let widths = table.resolve_widths(&table.render_options());
+        for (i, width) in widths.into_iter().enumerate() {
+            match shared.get_mut(i) {
+                Some(max) => *max = (*max).max(width),
+                None => shared.push(width),
+            }
+        }
+    }
+    shared
+}
+
+impl Table {
+    /// Keeps the column widths as they are now. Rows added later are
+    /// wrapped or truncated to them instead of widening their columns, so
+    /// streamed output stays aligned with what was already printed.
+    pub fn lock_widths(&mut self) {
+        self.locked_widths = Some(self.resolve_widths(&self.render_options()));
+    }
+
+    pub fn unlock_widths(&mut self) {
+        self.locked_widths = None;
+    }
+
+    pub(crate) fn resolve_widths(&self, options: &RenderOptions) -> Vec<usize> {
+        if let Some(locked) = &self.locked_widths {
+            if locked.len() == self.columns.len() {
+                return locked.clone();
+            }
+        }
+        let natural = self.natural_widths(options);
+        let chrome = self.chrome_width(options);
+        let widths = match self.total_width {
+            Some((width, distribution)) => {
+                distribute(&natural, width.saturating_sub(chrome), distribution)
+            }
+            None => natural,
+        };
+        match options.max_width {
+            Some(max) if chrome + widths.iter().sum::<usize>() > max => distribute(
+                &widths,
+                max.saturating_sub(chrome),
+                Distribution::ShrinkLongest,
+            ),
+            _ => widths,
+        }
+    }
+
+    /// Widens every column to at least `widths`, the content width at the
+    /// same position, so this table lines up with others sized alike.
+    pub(crate) fn widen_columns(&mut self, widths: &[usize]) {
+        let simple = style_config(self.style).is_none();
+        for (column, &width) in self.columns.iter_mut().zip(widths) {
+            column.width = column.width.max(width + usize::from(simple));
+        }
+    }
+
+    pub(crate) fn window_columns(&self, first: usize, width: usize) -> Vec<usize> {
+        let options = self.render_options();
+        let natural = self.natural_widths(&options);
+        let fits = |columns: &[usize]| {
+            chrome_for(&options, columns.len()) + columns.iter().map(|&i| natural[i]).sum::<usize>()
+                <= width
+        };
+        let (mut chosen, scrolling): (Vec<usize>, Vec<usize>) =
+            (0..self.columns.len()).partition(|&i| self.columns[i].pinned);
+        let pinned = chosen.len();
+        for i in scrolling.into_iter().skip(first) {
+            chosen.push(i);
+            if chosen.len() > pinned + 1 && !fits(&chosen) {
+                chosen.pop();
+                break;
+            }
+        }
+        chosen.sort_unstable();
+        chosen
+    }
+
+    /// Columns to hide, lowest priority first, until the rest fit into `width`.
+    pub(crate) fn hidden_columns(&self, options: &RenderOptions, width: usize) -> Vec<usize> {
+        let natural = self.natural_widths(options);
+        let mut shown: Vec<usize> = (0..self.columns.len()).collect();
+        let mut candidates: Vec<usize> = shown
+            .iter()
+            .copied()
+            .filter(|&i| !self.columns[i].pinned)
+            .filter(|&i| self.columns[i].priority.is_some())
+            .collect();
+        candidates.sort_by_key(|&i| (self.columns[i].priority, Reverse(i)));
+        let mut hidden = Vec::new();
+        for candidate in candidates {
+            let used =
+                chrome_for(options, shown.len()) + shown.iter().map(|&i| natural[i]).sum::<usize>();
+            if used <= width {
+                break;
+            }
+            shown.retain(|&i| i != candidate);
+            hidden.push(candidate);
+        }
+        hidden.sort_unstable();
+        hidden
+    }
+
+    fn natural_widths(&self, options: &RenderOptions) -> Vec<usize> {
+        (0..self.columns.len())
+            .map(|i| {
+                let column = &self.columns[i];
+                let data = self.data_width(i, options.style);
+                if column.wrap != Wrap::Off || !options.show_header {
+                    return data;
+                }
+                if self.vertical_header(i, options.style) {
+                    let tallest = column.title().chars().filter_map(|c| c.width()).max();
+                    data.max(tallest.unwrap_or(0))
+                } else {
+                    data.max(line_width(&column.title()))
+                }
+            })
+            .collect()
+    }
+
+    fn data_width(&self, index: usize, style: TableStyle) -> usize {
+        let column = &self.columns[index];
+        let declared = if style_config(style).is_none() {
+            column.width.saturating_sub(1)
+        } else {
+            column.width
+        };
+        if column.wrap != Wrap::Off {
+            return declared;
+        }
+        let footer = self.footer_cell(index).map_or(0, |cell| line_width(&cell));
+        let total = self
+            .grand_total
+            .as_ref()
+            .map_or(0, |total| line_width(&total[index]));
+        self.widest_cell(index).max(declared).max(footer).max(total)
+    }
+
+    /// Width of each cell in `row` as formatted for display.
+    pub(crate) fn cell_widths<'a>(&'a self, row: &'a [String]) -> impl Iterator<Item = usize> + 'a {
+        row.iter()
+            .zip(&self.columns)
+            .enumerate()
+            .map(|(index, (cell, column))| {
+                if column.raw {
+                    return cell.split('\n').map(visible_width).max().unwrap_or(0);
+                }
+                let cell = column.display(cell, None, Charset::Unicode, self.locale_of(index));
+                line_width(&cell)
+            })
+    }
+
+    pub(crate) fn vertical_header(&self, index: usize, style: TableStyle) -> bool {
+        match self.header_orientation {
+            HeaderOrientation::Horizontal => false,
+            HeaderOrientation::Vertical => true,
+            HeaderOrientation::Auto => {
+                line_width(&self.columns[index].title()) > 2 * self.data_width(index, style)
+            }
+        }
+    }
+
+    pub(crate) fn chrome_width(&self, options: &RenderOptions) -> usize {
+        chrome_for(options, self.columns.len())
+    }
+}
+
+/// Width taken by borders and padding for the given number of columns.
+fn chrome_for(options: &RenderOptions, columns: usize) -> usize {
+    match style_config(options.style) {
+        None => columns,
+        Some(style) => [&style.top, &style.below_header, &style.bottom]
+            .into_iter()
+            .filter(|line| !line.hline.is_empty())
+            .chain(std::iter::once(&style.row))
+            .map(|line| {
+                // Rules make up for wide glyphs; row lines cannot.
+                let measure = |glyph: &str| {
+                    if std::ptr::eq(line, &style.row) {
+                        options.border_width(glyph)
+                    } else {
+                        display_width(glyph)
+                    }
+                };
+                measure(line.begin)
+                    + measure(line.end)
+                    + columns.saturating_sub(1) * measure(line.sep)
+                    + 2 * cell_padding(options.style) * columns
+            })
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+/// Renders tables next to each other, `gap` spaces apart. Shorter tables are
+/// padded with blank lines at the bottom.
+pub fn side_by_side(tables: &[&Table], gap: usize) -> String {
+    let blocks: Vec<Vec<String>> = tables.iter().map(|table| table.render_lines()).collect();
+    join_blocks(&blocks, gap)
+}
+
+/// Arranges tables and titled panels into rows that fit a given width,
+/// wrapping to a new row of panels when the next one would not fit.
+pub struct Grid<'a> {
+    panels: Vec<(Option<String>, &'a Table)>,
+    gap: usize,
+    width: Option<usize>,
+}
+
+impl<'a> Grid<'a> {
+    pub fn new() -> Self {
+        Grid {
+            panels: Vec::new(),
+            gap: 2,
+            width: None,
+        }
+    }
+
+    pub fn add(&mut self, table: &'a Table) -> &mut Self {
+        self.panels.push((None, table));
+        self
+    }
+
+    pub fn add_panel(&mut self, title: &str, table: &'a Table) -> &mut Self {
+        self.panels.push((Some(title.to_string()), table));
+        self
+    }
+
+    pub fn gap(&mut self, gap: usize) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Overrides the terminal width, which defaults to 80 columns when it
+    /// cannot be detected.
+    pub fn width(&mut self, width: usize) -> &mut Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let available = self.width.unwrap_or_else(terminal_width);
+        let mut rows: Vec<Vec<(bool, Vec<String>)>> = Vec::new();
+        let mut used = 0;
+        for (title, table) in &self.panels {
+            let mut lines = table.render_lines();
+            if let Some(title) = title {
+                let width = block_width(&lines).max(display_width(title));
+                lines.insert(0, pad(title, width, Alignment::Center));
+            }
+            let width = block_width(&lines);
+            let panel = (title.is_some(), lines);
+            match rows.last_mut() {
+                Some(row) if used + self.gap + width <= available => {
+                    used += self.gap + width;
+                    row.push(panel);
+                }
+                _ => {
+                    used = width;
+                    rows.push(vec![panel]);
+                }
+            }
+        }
+        let rows: Vec<String> = rows
+            .into_iter()
+            .map(|row| {
+                // Keep tables level when only some panels in the row are titled.
+                let titled = row.iter().any(|(titled, _)| *titled);
+                let blocks: Vec<Vec<String>> = row
+                    .into_iter()
+                    .map(|(has_title, mut lines)| {
+                        if titled && !has_title {
+                            lines.insert(0, String::new());
+                        }
+                        lines
+                    })
+                    .collect();
+                join_blocks(&blocks, self.gap)
+            })
+            .collect();
+        rows.join("\n")
+    }
+
+    pub fn print(&self) -> io::Result<()> {
+        io::stdout().write_all(self.render().as_bytes())
+    }
+}
+
+impl Default for Grid<'_> {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+/// Width of the terminal on stdout, or 80 columns if there is none.
+pub(crate) fn terminal_width() -> usize {
+    terminal_size::terminal_size().map_or(80, |(width, _)| usize::from(width.0))
+}
+
+fn block_width(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .map(|line| display_width(line))
+        .max()
+        .unwrap_or(0)
+}
+
+fn join_blocks(blocks: &[Vec<String>], gap: usize) -> String {
+    let widths: Vec<usize> = blocks.iter().map(|lines| block_width(lines)).collect();
+    let height = blocks.iter().map(Vec::len).max().unwrap_or(0);
+    let gap = " ".repeat(gap);
+
+    let mut out = String::new();
+    for line in 0..height {
+        let mut joined = String::new();
+        for (i, (lines, width)) in blocks.iter().zip(&widths).enumerate() {
+            if i > 0 {
+                joined.push_str(&gap);
+            }
+            let text = lines.get(line).map_or("", String::as_str);
+            joined.push_str(&pad(text, *width, Alignment::Left));
+        }
+        out.push_str(joined.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn distribute(natural: &[usize], available: usize, distribution: Distribution) -> Vec<usize> {
+    let columns = natural.len();
+    if columns == 0 {
+        return Vec::new();
+    }
+    let available = available.max(columns);
+    match distribution {
+        Distribution::Even => spread(&vec![1; columns], available),
+        Distribution::Weighted => spread(natural, available),
+        Distribution::ShrinkLongest => {
+            let total: usize = natural.iter().sum();
+            if total <= available {
+                let extra = spread(&vec![1; columns], available - total + columns);
+                return natural.iter().zip(extra).map(|(n, e)| n + e - 1).collect();
+            }
+            let mut widths = natural.to_vec();
+            let mut excess = total - available;
+            while excess > 0 {
+                let widest = widths.iter().copied().max().unwrap_or(0);
+                let Some(i) = widths.iter().position(|&w| w == widest) else {
+                    break;
+                };
+                widths[i] -= 1;
+                excess -= 1;
+            }
+            widths
+        }
+    }
+}
+
+fn spread(weights: &[usize], available: usize) -> Vec<usize> {
+    let columns = weights.len();
+    let total_weight: usize = weights.iter().map(|w| (*w).max(1)).sum();
+    let budget = available - columns;
+    let mut widths: Vec<usize> = weights
+        .iter()
+        .map(|w| 1 + budget * (*w).max(1) / total_weight)
+        .collect();
+    let mut remainder = available - widths.iter().sum::<usize>();
+    let mut i = 0;
+    while remainder > 0 {
+        widths[i % columns] += 1;
+        remainder -= 1;
+        i += 1;
+    }
+    widths
+}
+
+fn line_width(text: &str) -> usize {
+    text.split('\n').map(display_width).max().unwrap_or(0)
+}
@@ -4,13 +4,114 @@
 // Author: Volker Schwaberow <volker@schwaberow.de>
 // Copyright (c) 2024 Volker Schwaberow
 
+use std::collections::HashMap;
+use std::fmt;
 use std::io::{self, Write};
-use termcolor::{ColorChoice, ColorSpec, StandardStream, WriteColor};
+#[cfg(feature = "color")]
+use termcolor::{Ansi, BufferWriter, ColorChoice};
+
+mod aggregate;
+#[cfg(feature = "bidi")]
+mod bidi;
+mod border;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod color;
+mod columns;
+mod computed;
+mod concurrent;
+#[cfg(feature = "csv")]
+mod csv_backed;
+mod error;
+mod export;
+mod exporter;
+mod expr;
+mod fast;
+mod format;
+mod highlight;
+mod html;
+#[cfg(feature = "csv")]
+mod import;
+mod importer;
+mod index;
+#[cfg(feature = "tui")]
+mod interact;
+#[cfg(feature = "json")]
+mod json;
+pub mod layout;
+mod link;
+mod locale;
+mod machine;
+mod options;
+#[cfg(feature = "pager")]
+mod pager;
+mod paginate;
+mod parse;
+mod policy;
+#[cfg(feature = "json")]
+mod project;
+#[cfg(feature = "http")]
+mod remote;
+mod render;
+mod report;
+mod responsive;
+mod row_view;
+mod stats;
+mod term;
+mod text;
+mod transform;
+mod validate;
+mod view;
+mod watch;
+mod wrap;
 
 #[cfg(test)]
 mod tests;
 
-#[derive(Clone, Copy, Debug)]
+pub use aggregate::{Aggregate, FOOTER_ERROR};
+#[cfg(feature = "bidi")]
+pub use bidi::Bidi;
+pub use border::{BorderKind, BorderPainter, BorderPosition, BorderSegment};
+#[cfg(feature = "clipboard")]
+pub use clipboard::ClipboardFormat;
+#[cfg(not(feature = "color"))]
+pub use color::WriteColor;
+pub use color::{Color, ColorSpec};
+pub use columns::ColumnRef;
+pub use concurrent::ConcurrentTableBuilder;
+#[cfg(feature = "csv")]
+pub use csv_backed::CsvBackedTable;
+pub use error::{ParseEnumError, RowErrors, TableError};
+pub use exporter::{Exporter, Exporters};
+pub use format::{humanize_bytes, humanize_count, DurationUnit, Format};
+#[cfg(feature = "csv")]
+pub use import::{sniff_csv, CsvDialect, CsvImportOptions, ImportReport, Selection};
+pub use importer::{ImportOptions, Importer, Importers};
+pub use index::IndexFormat;
+pub use layout::{Distribution, Grid, Layout};
+pub use locale::Locale;
+pub use machine::Machine;
+pub use options::{Charset, RenderOptions, Theme, DEFAULT_WIDTH};
+pub use paginate::PaginationOptions;
+pub use policy::{EmptyPolicy, RowPolicy};
+#[cfg(feature = "http")]
+pub use remote::DataFormat;
+#[cfg(feature = "color")]
+use render::ColorSink;
+pub use render::RenderIter;
+use render::{FmtSink, Indented, PlainSink};
+pub use report::{preview_styles, print_all, write_all};
+pub use responsive::LayoutVariant;
+pub use row_view::{CellView, FromCell, RowView};
+pub use term::{ColorDepth, TermCaps};
+#[cfg(feature = "color")]
+pub use termcolor::WriteColor;
+pub use text::{display_width, visible_width};
+pub use validate::Validator;
+pub use watch::{RowUpdate, TableWatcher};
+pub use wrap::{Truncate, Wrap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TableStyle {
     Simple,
     Grid,
@@ -28,6 +129,26 @@ pub enum TableStyle {
     Neon,
 }
 
+impl TableStyle {
+    /// Every built-in style, in declaration order.
+    pub const ALL: [TableStyle; 14] = [
+        TableStyle::Simple,
+        TableStyle::Grid,
+        TableStyle::FancyGrid,
+        TableStyle::Clean,
+        TableStyle::Round,
+        TableStyle::Banner,
+        TableStyle::Block,
+        TableStyle::Amiga,
+        TableStyle::Minimal,
+        TableStyle::Compact,
+        TableStyle::Markdown,
+        TableStyle::Dotted,
+        TableStyle::Heavy,
+        TableStyle::Neon,
+    ];
+}
+
 #[derive(Clone, Copy)]
 pub enum Alignment {
     Left,
@@ -35,6 +156,24 @@ pub enum Alignment {
     Right,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeaderOrientation {
+    #[default]
+    Horizontal,
+    /// Render every header one character per line.
+    Vertical,
+    /// Render a header vertically when it is more than twice as wide as its column's data.
+    Auto,
+}
+
 struct LineStyle {
     begin: &'static str,
     hline: &'static str,
@@ -50,20 +189,27 @@ struct TableStyleConfig {
 }
 
 macro_rules! define_styles {
-    ($($name:ident: {
+    ($($(#[$meta:meta])* $name:ident: {
         $($field:ident: {
             $($inner_field:ident: $value:expr),+
         $(,)?}),+
     $(,)?}),+) => {
-        const STYLES: [TableStyleConfig; 14] = [
-            $(
-                TableStyleConfig {
-                    $($field: LineStyle {
-                        $($inner_field: $value,)+
-                    },)+
-                },
-            )+
-        ];
+        /// Looks up the line set for `style`. Styles compiled out by a
+        /// disabled feature fall back to `Grid`.
+        fn style_lines(style: TableStyle) -> &'static TableStyleConfig {
+            #[allow(unreachable_patterns)]
+            match style {
+                $(
+                    $(#[$meta])*
+                    TableStyle::$name => &TableStyleConfig {
+                        $($field: LineStyle {
+                            $($inner_field: $value,)+
+                        },)+
+                    },
+                )+
+                _ => style_lines(TableStyle::Grid),
+            }
+        }
     };
 }
 
@@ -80,30 +226,35 @@ define_styles! {
         bottom: { begin: "+", hline: "-", sep: "+", end: "+" },
         row: { begin: "|", hline: "", sep: "|", end: "|" }
     },
+    #[cfg(feature = "styles-unicode")]
     FancyGrid: {
         top: { begin: "╒", hline: "═", sep: "╤", end: "╕" },
         below_header: { begin: "╞", hline: "═", sep: "╪", end: "╡" },
         bottom: { begin: "╘", hline: "═", sep: "╧", end: "╛" },
         row: { begin: "│", hline: "", sep: "│", end: "│" }
     },
+    #[cfg(feature = "styles-unicode")]
     Clean: {
         top: { begin: "", hline: "─", sep: " ", end: "" },
         below_header: { begin: "", hline: "─", sep: " ", end: "" },
         bottom: { begin: "", hline: "─", sep: " ", end: "" },
         row: { begin: "", hline: "", sep: " ", end: "" }
     },
+    #[cfg(feature = "styles-unicode")]
     Round: {
         top: { begin: "╭", hline: "─", sep: "┬", end: "╮" },
         below_header: { begin: "├", hline: "─", sep: "┼", end: "┤" },
         bottom: { begin: "╰", hline: "─", sep: "┴", end: "╯" },
         row: { begin: "│", hline: "", sep: "│", end: "│" }
     },
+    #[cfg(feature = "styles-unicode")]
     Banner: {
         top: { begin: "╒", hline: "═", sep: "╤", end: "╕" },
         below_header: { begin: "╘", hline: "═", sep: "╧", end: "╛" },
         bottom: { begin: "╘", hline: "═", sep: "╧", end: "╛" },
         row: { begin: "│", hline: "", sep: "│", end: "│" }
     },
+    #[cfg(feature = "styles-unicode")]
     Block: {
         top: { begin: "◢", hline: "■", sep: "■", end: "◣" },
         below_header: { begin: " ", hline: "━", sep: "━", end: " " },
@@ -116,17 +267,19 @@ define_styles! {
         bottom: { begin: "", hline: "", sep: "", end: "" },
         row: { begin: "", hline: "", sep: "", end: "" }
     },
-     Minimal: {
+    #[cfg(feature = "styles-unicode")]
+    Minimal: {
         top: { begin: "┌", hline: "─", sep: "┬", end: "┐" },
         below_header: { begin: "├", hline: "─", sep: "┼", end: "┤" },
         bottom: { begin: "└", hline: "─", sep: "┴", end: "┘" },
         row: { begin: "│", hline: "", sep: "│", end: "│" }
     },
+    #[cfg(feature = "styles-unicode")]
     Compact: {
-        top: { begin: "┌", hline: "─", sep: "┬", end: "┐" },
-        below_header: { begin: "├", hline: "─", sep: "┼", end: "┤" },
-        bottom: { begin: "└", hline: "─", sep: "┴", end: "┘" },
-        row: { begin: "│", hline: "", sep: "│", end: "│" }
+        top: { begin: "", hline: "", sep: "", end: "" },
+        below_header: { begin: "", hline: "─", sep: "┼", end: "" },
+        bottom: { begin: "", hline: "", sep: "", end: "" },
+        row: { begin: "", hline: "", sep: "│", end: "" }
     },
     Markdown: {
         top: { begin: "", hline: "", sep: "", end: "" },
@@ -140,12 +293,14 @@ define_styles! {
         bottom: { begin: "'", hline: ".", sep: "'", end: "'" },
         row: { begin: ":", hline: "", sep: ":", end: ":" }
     },
+    #[cfg(feature = "styles-unicode")]
     Heavy: {
         top: { begin: "┏", hline: "━", sep: "┳", end: "┓" },
         below_header: { begin: "┣", hline: "━", sep: "╋", end: "┫" },
         bottom: { begin: "┗", hline: "━", sep: "┻", end: "┛" },
         row: { begin: "┃", hline: "", sep: "┃", end: "┃" }
     },
+    #[cfg(feature = "styles-unicode")]
     Neon: {
         top: { begin: "┏", hline: "━", sep: "┳", end: "┓" },
         below_header: { begin: "┣", hline: "━", sep: "╋", end: "┫" },
@@ -154,41 +309,260 @@ define_styles! {
     }
 }
 
+#[derive(Clone)]
 pub struct Column {
     header: String,
     width: usize,
     alignment: Alignment,
+    header_alignment: Option<Alignment>,
+    wrap: Wrap,
+    vertical_alignment: VerticalAlignment,
+    compute: Option<computed::ComputeFn>,
+    validator: Option<Validator>,
+    pinned: bool,
+    priority: Option<u32>,
+    truncate: Truncate,
+    marker: String,
+    signed_colors: bool,
+    format: Format,
+    locale: Option<Locale>,
+    link_template: Option<String>,
+    raw: bool,
+    unit: Option<String>,
+    unit_in_header: bool,
+    description: Option<String>,
+    #[cfg(feature = "regex")]
+    highlights: Vec<(regex::Regex, ColorSpec)>,
+}
+
+impl Column {
+    fn new(header: &str, width: usize, alignment: Alignment) -> Self {
+        Column {
+            header: header.to_string(),
+            width,
+            alignment,
+            header_alignment: None,
+            wrap: Wrap::Off,
+            vertical_alignment: VerticalAlignment::Top,
+            compute: None,
+            validator: None,
+            pinned: false,
+            priority: None,
+            truncate: Truncate::End,
+            marker: text::ELLIPSIS.to_string(),
+            signed_colors: false,
+            link_template: None,
+            raw: false,
+            unit: None,
+            unit_in_header: false,
+            description: None,
+            format: Format::Plain,
+            locale: None,
+            #[cfg(feature = "regex")]
+            highlights: Vec::new(),
+        }
+    }
+
+    /// Aligns the header independently of the data below it.
+    pub fn header_alignment(&mut self, alignment: Alignment) -> &mut Self {
+        self.header_alignment = Some(alignment);
+        self
+    }
+
+    pub fn wrap(&mut self, wrap: Wrap) -> &mut Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn truncate(&mut self, position: Truncate) -> &mut Self {
+        self.truncate = position;
+        self
+    }
+
+    /// Text that stands in for the cut part of truncated cells; "…" by
+    /// default. With `Charset::Ascii` a non-ASCII marker is drawn as "...".
+    pub fn truncation_marker(&mut self, marker: &str) -> &mut Self {
+        self.marker = marker.to_string();
+        self
+    }
+
+    pub fn vertical_alignment(&mut self, alignment: VerticalAlignment) -> &mut Self {
+        self.vertical_alignment = alignment;
+        self
+    }
+
+    /// Keeps the column visible when the table is shown a few columns at a
+    /// time, e.g. for an identifying name or ID column.
+    pub fn pinned(&mut self, pinned: bool) -> &mut Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Lets the column be hidden when the table is too wide; columns with
+    /// the lowest priority go first. Columns without one are always shown.
+    pub fn priority(&mut self, priority: u32) -> &mut Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// What the column holds, for HTML `title` attributes, Markdown
+    /// footnotes and the help screen of `interact`. Terminal tables do not
+    /// show it.
+    pub fn description(&mut self, description: &str) -> &mut Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Passes cells through untouched, for content already styled with
+    /// ANSI escapes. Cells are sized by their visible text and are never
+    /// formatted, wrapped or truncated, so keep them within the width.
+    pub fn raw(&mut self, raw: bool) -> &mut Self {
+        self.raw = raw;
+        self
+    }
 }
 
 pub struct Table {
     columns: Vec<Column>,
     rows: Vec<Vec<String>>,
     style: TableStyle,
+    total_width: Option<(usize, Distribution)>,
+    header_orientation: HeaderOrientation,
+    max_row_height: Option<usize>,
+    max_rows: Option<usize>,
+    title: Option<String>,
+    footers: Vec<(usize, Aggregate)>,
+    /// Footer over all rows, drawn below the page's own on the last page.
+    grand_total: Option<Vec<String>>,
+    strict: bool,
+    violations: Vec<TableError>,
+    legend: Vec<(ColorSpec, String)>,
+    border_color: Option<ColorSpec>,
+    border_painter: Option<std::sync::Arc<dyn BorderPainter>>,
+    stats: stats::StatsCache,
+    glyph_widths: Vec<(char, usize)>,
+    trim_trailing_spaces: Option<bool>,
+    right_to_left: bool,
+    repeat_header_every: Option<usize>,
+    index: Option<index::Index>,
+    locked_widths: Option<Vec<usize>>,
+    row_highlights: HashMap<usize, ColorSpec>,
+    cell_highlights: HashMap<(usize, usize), ColorSpec>,
+    cell_meta: HashMap<(usize, usize), html::CellMeta>,
+    column_paging: bool,
+    breakpoints: responsive::Breakpoints,
+    default_cell: String,
+    row_policy: RowPolicy,
+    empty_policy: EmptyPolicy,
+    locale: Locale,
+    #[cfg(feature = "csv")]
+    import_report: ImportReport,
+    #[cfg(feature = "bidi")]
+    bidi: Bidi,
 }
 
+// Tables are built on worker threads and rendered elsewhere; keep the
+// public types Send + Sync. The watcher owns a channel receiver, which is
+// only Send.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Table>();
+    assert_send_sync::<Column>();
+    assert_send_sync::<Layout>();
+    assert_send_sync::<TableError>();
+    assert_send_sync::<ConcurrentTableBuilder>();
+    assert_send_sync::<RowUpdate>();
+    assert_send::<TableWatcher>();
+};
+
 impl Table {
     pub fn new(style: TableStyle) -> Self {
         Table {
             columns: Vec::new(),
             rows: Vec::new(),
             style,
+            total_width: None,
+            header_orientation: HeaderOrientation::Horizontal,
+            max_row_height: None,
+            max_rows: None,
+            title: None,
+            footers: Vec::new(),
+            grand_total: None,
+            strict: false,
+            violations: Vec::new(),
+            legend: Vec::new(),
+            border_color: None,
+            border_painter: None,
+            stats: Default::default(),
+            glyph_widths: Vec::new(),
+            trim_trailing_spaces: None,
+            right_to_left: false,
+            repeat_header_every: None,
+            index: None,
+            locked_widths: None,
+            row_highlights: HashMap::new(),
+            cell_highlights: HashMap::new(),
+            cell_meta: HashMap::new(),
+            column_paging: false,
+            breakpoints: Vec::new(),
+            default_cell: String::new(),
+            row_policy: RowPolicy::Exact,
+            empty_policy: EmptyPolicy::ShowHeaders,
+            locale: Locale::default(),
+            #[cfg(feature = "csv")]
+            import_report: ImportReport::default(),
+            #[cfg(feature = "bidi")]
+            bidi: Bidi::Off,
         }
     }
 
-    pub fn from_csv(path: &str) -> io::Result<Self> {
-        let mut reader = csv::Reader::from_path(path)?;
-        let headers = reader.headers()?;
-        let mut table = Table::new(TableStyle::Simple);
-        for header in headers {
-            table.add_column(header, 10, Alignment::Left);
-        }
-        for result in reader.records() {
-            let record = result?;
-            table.add_row(record.iter().map(|s| s.to_string()).collect());
+    pub(crate) fn empty_like(&self) -> Table {
+        Table {
+            columns: self.columns.clone(),
+            rows: Vec::new(),
+            style: self.style,
+            total_width: self.total_width,
+            header_orientation: self.header_orientation,
+            max_row_height: self.max_row_height,
+            max_rows: self.max_rows,
+            title: self.title.clone(),
+            footers: self.footers.clone(),
+            grand_total: self.grand_total.clone(),
+            strict: self.strict,
+            violations: Vec::new(),
+            legend: self.legend.clone(),
+            border_color: self.border_color.clone(),
+            border_painter: self.border_painter.clone(),
+            stats: Default::default(),
+            glyph_widths: self.glyph_widths.clone(),
+            trim_trailing_spaces: self.trim_trailing_spaces,
+            right_to_left: self.right_to_left,
+            repeat_header_every: self.repeat_header_every,
+            index: self.index.clone(),
+            locked_widths: self.locked_widths.clone(),
+            row_highlights: HashMap::new(),
+            cell_highlights: HashMap::new(),
+            cell_meta: HashMap::new(),
+            column_paging: self.column_paging,
+            breakpoints: self.breakpoints.clone(),
+            default_cell: self.default_cell.clone(),
+            row_policy: self.row_policy,
+            empty_policy: self.empty_policy.clone(),
+            locale: self.locale,
+            #[cfg(feature = "csv")]
+            import_report: ImportReport::default(),
+            #[cfg(feature = "bidi")]
+            bidi: self.bidi,
         }
-        Ok(table)
     }
 
+    #[cfg(feature = "csv")]
+    pub fn from_csv(path: &str) -> Result<Self, TableError> {
+        Table::from_csv_with_options(path, &CsvImportOptions::default())
+    }
+
+    #[cfg(feature = "csv")]
     pub fn to_csv(&self, path: &str) -> io::Result<()> {
         let mut writer = csv::Writer::from_path(path)?;
         for row in &self.rows {
@@ -198,176 +572,152 @@ impl Table {
     }
 
     pub fn print_to_writer(&self, writer: &mut dyn Write) -> io::Result<()> {
-        match self.style {
-            TableStyle::Simple => self.print_simple(writer),
-            TableStyle::Grid => self.print_styled(writer, &STYLES[1]),
-            TableStyle::FancyGrid => self.print_styled(writer, &STYLES[2]),
-            TableStyle::Clean => self.print_styled(writer, &STYLES[3]),
-            TableStyle::Round => self.print_styled(writer, &STYLES[4]),
-            TableStyle::Banner => self.print_styled(writer, &STYLES[5]),
-            TableStyle::Block => self.print_styled(writer, &STYLES[6]),
-            TableStyle::Amiga => self.print_simple(writer),
-            TableStyle::Minimal => self.print_simple(writer),
-            TableStyle::Compact => self.print_simple(writer),
-            TableStyle::Markdown => self.print_simple(writer),
-            TableStyle::Dotted => self.print_simple(writer),
-            TableStyle::Heavy => self.print_simple(writer),
-            TableStyle::Neon => self.print_simple(writer),
+        let options = self.render_options();
+        if self.fast_path(&options) {
+            return self.write_fast(writer, &options);
         }
+        self.render_with(&mut PlainSink(writer), &options)
+    }
+
+    /// Renders into a `fmt::Write` such as a `String`, without colors.
+    pub fn render_fmt<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        self.render(&mut FmtSink(writer)).map_err(|_| fmt::Error)
+    }
+
+    /// Writes the table from a `Display` impl. A width on the formatter,
+    /// as in `{:4}`, indents every line by that many spaces.
+    pub fn fmt_into(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = " ".repeat(f.width().unwrap_or(0));
+        self.fmt_indented(f, &indent)
+    }
+
+    /// Like `fmt_into`, prefixing every line with `indent`.
+    pub fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        self.render_fmt(&mut Indented::new(f, indent))
     }
 
     pub fn add_column(&mut self, header: &str, width: usize, alignment: Alignment) {
-        self.columns.push(Column {
-            header: header.to_string(),
-            width,
-            alignment,
-        });
+        self.columns.push(Column::new(header, width, alignment));
+        self.invalidate_stats();
+    }
+
+    #[cfg(feature = "bidi")]
+    pub fn set_bidi(&mut self, bidi: Bidi) {
+        self.bidi = bidi;
+    }
+
+    pub fn set_header_orientation(&mut self, orientation: HeaderOrientation) {
+        self.header_orientation = orientation;
+    }
+
+    pub fn set_max_row_height(&mut self, lines: usize) {
+        self.max_row_height = Some(lines.max(1));
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = Some(title.to_string());
+    }
+
+    /// Draws the frame characters in `spec`, leaving cell text alone.
+    pub fn set_border_color(&mut self, spec: ColorSpec) {
+        self.border_color = Some(spec);
+    }
+
+    pub fn set_max_rows(&mut self, rows: usize) {
+        self.max_rows = Some(rows);
+    }
+
+    /// Explains the colors used in the table with a line of swatches below it.
+    pub fn set_legend(&mut self, entries: &[(ColorSpec, &str)]) {
+        self.legend = entries
+            .iter()
+            .map(|(spec, label)| (spec.clone(), label.to_string()))
+            .collect();
+    }
+
+    pub fn column_mut(&mut self, column: impl Into<ColumnRef>) -> Result<&mut Column, TableError> {
+        let index = self.column_index(column)?;
+        Ok(&mut self.columns[index])
     }
 
+    /// Invalid cells are recorded in `violations()` even in strict mode;
+    /// use `try_add_row` to have such rows rejected.
     pub fn add_row(&mut self, row: Vec<String>) {
+        let row = self.complete_row(row);
         assert_eq!(
             self.columns.len(),
             row.len(),
             "Row length must match columns"
         );
+        self.record_violations(&row);
         self.rows.push(row);
     }
 
-    pub fn print(&self) -> io::Result<()> {
-        let mut stdout = StandardStream::stdout(ColorChoice::Always);
-        self.print_color(&mut stdout)
-    }
-
-    pub fn print_color<W: Write + WriteColor>(&self, writer: &mut W) -> io::Result<()> {
-        match self.style {
-            TableStyle::Simple => self.print_simple(writer),
-            TableStyle::Grid => self.print_styled(writer, &STYLES[1]),
-            TableStyle::FancyGrid => self.print_styled(writer, &STYLES[2]),
-            TableStyle::Clean => self.print_styled(writer, &STYLES[3]),
-            TableStyle::Round => self.print_styled(writer, &STYLES[4]),
-            TableStyle::Banner => self.print_styled(writer, &STYLES[5]),
-            TableStyle::Block => self.print_styled(writer, &STYLES[6]),
-            TableStyle::Amiga => self.print_amiga_color(writer),
-            TableStyle::Minimal => self.print_styled(writer, &STYLES[7]),
-            TableStyle::Compact => self.print_styled(writer, &STYLES[8]),
-            TableStyle::Markdown => self.print_styled(writer, &STYLES[9]),
-            TableStyle::Dotted => self.print_styled(writer, &STYLES[10]),
-            TableStyle::Heavy => self.print_styled(writer, &STYLES[11]),
-            TableStyle::Neon => self.print_styled(writer, &STYLES[12]),
-        }
+    pub fn sort_by_cached_key<K, F>(
+        &mut self,
+        column: impl Into<ColumnRef>,
+        mut f: F,
+    ) -> Result<(), TableError>
+    where
+        K: Ord,
+        F: FnMut(&str) -> K,
+    {
+        let column = self.column_index(column)?;
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by_cached_key(|&i| f(&self.rows[i][column]));
+        self.reorder_rows(&order);
+        Ok(())
     }
 
-    fn print_amiga_color<W: Write + WriteColor>(&self, stream: &mut W) -> io::Result<()> {
-        let mut color_spec = ColorSpec::new();
-        color_spec.set_fg(Some(termcolor::Color::Blue));
-
-        stream.set_color(&color_spec)?;
-        self.print_headers(stream)?;
-
-        color_spec.set_fg(Some(termcolor::Color::White));
-        stream.set_color(&color_spec)?;
-
-        self.rows
-            .iter()
-            .try_for_each(|row| self.print_row(stream, row))
-    }
-
-    fn print_headers(&self, writer: &mut dyn Write) -> io::Result<()> {
-        for (i, column) in self.columns.iter().enumerate() {
-            match column.alignment {
-                Alignment::Left => write!(
-                    writer,
-                    "{:<width$}",
-                    column.header,
-                    width = column.width - 1
-                )?,
-                Alignment::Center => write!(
-                    writer,
-                    "{:^width$}",
-                    column.header,
-                    width = column.width - 1
-                )?,
-                Alignment::Right => write!(
-                    writer,
-                    "{:>width$}",
-                    column.header,
-                    width = column.width - 1
-                )?,
-            }
-            if i < self.columns.len() - 1 {
-                write!(writer, " ")?;
-            }
-        }
-        writeln!(writer)
+    /// Renders the whole table into a buffer first and writes it to stdout
+    /// in one go, which avoids flicker on slow consoles. Charset, colors
+    /// and width follow what `TermCaps::detect` finds.
+    #[cfg(feature = "color")]
+    pub fn print(&self) -> io::Result<()> {
+        let options = self.render_options().for_terminal(&TermCaps::detect());
+        let stdout = BufferWriter::stdout(ColorChoice::Always);
+        let mut buffer = stdout.buffer();
+        self.render_with(&mut ColorSink(&mut buffer), &options)?;
+        stdout.print(&buffer)
     }
 
-    fn print_row(&self, writer: &mut dyn Write, row: &[String]) -> io::Result<()> {
-        for (column, cell) in self.columns.iter().zip(row.iter()) {
-            match column.alignment {
-                Alignment::Left => write!(writer, "{:<width$}", cell, width = column.width - 1)?,
-                Alignment::Center => write!(writer, "{:^width$}", cell, width = column.width - 1)?,
-                Alignment::Right => write!(writer, "{:>width$}", cell, width = column.width - 1)?,
-            }
-            write!(writer, " ")?;
-        }
-        writeln!(writer)
+    /// Without the `color` feature, the table goes to stdout uncolored.
+    #[cfg(not(feature = "color"))]
+    pub fn print(&self) -> io::Result<()> {
+        let options = self.render_options().for_terminal(&TermCaps::detect());
+        let mut buffer = Vec::new();
+        self.render_with(&mut PlainSink(&mut buffer), &options)?;
+        io::stdout().lock().write_all(&buffer)
     }
 
-    fn print_line(&self, writer: &mut dyn Write, style: &LineStyle) -> io::Result<()> {
-        write!(writer, "{}", style.begin)?;
-        for (i, column) in self.columns.iter().enumerate() {
-            if i > 0 {
-                write!(writer, "{}", style.sep)?;
-            }
-            write!(writer, "{}", style.hline.repeat(column.width + 2))?;
-        }
-        writeln!(writer, "{}", style.end)
+    /// Writes the table in color to a writer such as termcolor's
+    /// `StandardStream`, which decides whether and how colors are shown.
+    /// Without the `color` feature the output is plain.
+    pub fn print_color<W: WriteColor>(&self, writer: &mut W) -> io::Result<()> {
+        self.print_with(writer, &self.render_options())
     }
 
-    fn print_row_styled(
+    /// Like `print_color`, with `options` in place of the table's own.
+    pub fn print_with<W: WriteColor>(
         &self,
-        writer: &mut dyn Write,
-        row: &[impl AsRef<str>],
-        style: &LineStyle,
+        writer: &mut W,
+        options: &RenderOptions,
     ) -> io::Result<()> {
-        write!(writer, "{}", style.begin)?;
-        for (i, (cell, column)) in row.iter().zip(self.columns.iter()).enumerate() {
-            if i > 0 {
-                write!(writer, "{}", style.sep)?;
-            }
-            match column.alignment {
-                Alignment::Left => {
-                    write!(writer, " {:<width$} ", cell.as_ref(), width = column.width)?
-                }
-                Alignment::Center => {
-                    write!(writer, " {:^width$} ", cell.as_ref(), width = column.width)?
-                }
-                Alignment::Right => {
-                    write!(writer, " {:>width$} ", cell.as_ref(), width = column.width)?
-                }
-            }
-        }
-        writeln!(writer, "{}", style.end)
-    }
-
-    fn print_simple(&self, writer: &mut dyn Write) -> io::Result<()> {
-        self.print_headers(writer)?;
-        self.rows
-            .iter()
-            .try_for_each(|row| self.print_row(writer, row))
+        #[cfg(feature = "color")]
+        self.render_with(&mut ColorSink(&mut *writer), options)?;
+        #[cfg(not(feature = "color"))]
+        self.render_with(&mut PlainSink(&mut *writer), options)?;
+        writer.flush()
     }
 
-    fn print_styled(&self, writer: &mut dyn Write, style: &TableStyleConfig) -> io::Result<()> {
-        self.print_line(writer, &style.top)?;
-        self.print_row_styled(
-            writer,
-            &self.columns.iter().map(|c| &c.header).collect::<Vec<_>>(),
-            &style.row,
-        )?;
-        self.print_line(writer, &style.below_header)?;
-        for row in &self.rows {
-            self.print_row_styled(writer, row, &style.row)?;
-        }
-        self.print_line(writer, &style.bottom)
+    /// Writes the table to any writer with colors and hyperlinks as ANSI
+    /// escape codes, e.g. for a pager or a log viewer that shows them.
+    /// Without the `color` feature the output is plain.
+    pub fn print_ansi<W: Write>(&self, writer: &mut W, options: &RenderOptions) -> io::Result<()> {
+        #[cfg(feature = "color")]
+        self.render_with(&mut ColorSink(&mut Ansi::new(&mut *writer)), options)?;
+        #[cfg(not(feature = "color"))]
+        self.render_with(&mut PlainSink(&mut *writer), options)?;
+        writer.flush()
     }
 }
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/link.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::fmt::Write;
+
+use crate::Column;
+
+impl Column {
+    /// Makes every non-empty cell a terminal hyperlink (OSC 8) to
+    /// `template` with `{value}` replaced by the percent-encoded cell text.
+    /// Only color output carries links; plain output shows the text alone.
+    pub fn link_template(&mut self, template: &str) -> &mut Self {
+        self.link_template = Some(template.to_string());
+        self
+    }
+
+    pub(crate) fn link(&self, cell: &str) -> Option<String> {
+        let template = self.link_template.as_deref()?;
+        let value = cell.trim();
+        if value.is_empty() {
+            return None;
+        }
+        Some(template.replace("{value}", &percent_encode(value)))
+    }
+}
+
+/// Escapes everything outside the URL unreserved set.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{:02X}", byte);
+        }
+    }
+    encoded
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/locale.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::{Column, Table};
+
+/// Decimal and digit grouping separators, used when formatting numbers and
+/// when reading cells for sorting and aggregates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Locale {
+    pub decimal: char,
+    /// Inserted between groups of three digits in formatted output; `None`
+    /// leaves the digits ungrouped.
+    pub grouping: Option<char>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            decimal: '.',
+            grouping: None,
+        }
+    }
+}
+
+impl Locale {
+    /// `1,234.56`
+    pub const EN: Locale = Locale {
+        decimal: '.',
+        grouping: Some(','),
+    };
+    /// `1.234,56`
+    pub const DE: Locale = Locale {
+        decimal: ',',
+        grouping: Some('.'),
+    };
+    /// `1 234,56`, grouped with a narrow no-break space.
+    pub const FR: Locale = Locale {
+        decimal: ',',
+        grouping: Some('\u{202f}'),
+    };
+
+    /// Reads a number, ignoring grouping separators, underscores and spaces.
+    pub(crate) fn parse(&self, cell: &str) -> Option<f64> {
+        self.clean(cell).parse().ok()
+    }
+
+    /// Like `parse`, for whole numbers that must not lose precision.
+    pub(crate) fn parse_integer(&self, cell: &str) -> Option<i64> {
+        self.clean(cell).parse().ok()
+    }
+
+    fn clean(&self, cell: &str) -> String {
+        let other = if self.decimal == ',' { '.' } else { ',' };
+        cell.trim()
+            .chars()
+            .filter(|&c| Some(c) != self.grouping && c != other && c != '_' && !c.is_whitespace())
+            .map(|c| if c == self.decimal { '.' } else { c })
+            .collect()
+    }
+
+    pub(crate) fn format(&self, value: f64, decimals: usize) -> String {
+        self.localize(&format!("{:.*}", decimals, value))
+    }
+
+    /// Rewrites a number formatted by Rust, such as `-1234.5` or `1.2e-5`,
+    /// with this locale's separators.
+    pub(crate) fn localize(&self, number: &str) -> String {
+        let (sign, rest) = match number.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", number),
+        };
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (integer, tail) = rest.split_at(digits);
+        let mut localized = String::with_capacity(number.len() + digits / 3);
+        localized.push_str(sign);
+        for (i, c) in integer.chars().enumerate() {
+            if let Some(grouping) = self.grouping {
+                if i > 0 && (digits - i).is_multiple_of(3) {
+                    localized.push(grouping);
+                }
+            }
+            localized.push(c);
+        }
+        localized.extend(
+            tail.chars()
+                .map(|c| if c == '.' { self.decimal } else { c }),
+        );
+        localized
+    }
+}
+
+impl Column {
+    /// Overrides the table's locale for this column.
+    pub fn locale(&mut self, locale: Locale) -> &mut Self {
+        self.locale = Some(locale);
+        self
+    }
+}
+
+impl Table {
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    pub(crate) fn locale_of(&self, column: usize) -> Locale {
+        self.columns[column].locale.unwrap_or(self.locale)
+    }
+}
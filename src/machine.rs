@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/machine.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::borrow::Cow;
+use std::io;
+
+use crate::render::Sink;
+use crate::text::display_width;
+use crate::{RenderOptions, Table};
+
+/// Output for scripts rather than people: no borders, colors, title or
+/// truncation, and every row on one line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Machine {
+    /// Columns aligned with spaces, for `awk` and `cut -c`. Spaces inside a
+    /// cell become `_`, other whitespace a backslash escape, and empty cells
+    /// `-`, so splitting on runs of blanks always yields one field per
+    /// column. Literal `_`, `\\` and a lone `-` are escaped with a backslash;
+    /// `Machine::decode_field` turns a field back into its cell.
+    Spaces,
+    /// Cells separated by a single tab, unaligned; tabs and line breaks in
+    /// cells become spaces.
+    Tabs,
+}
+
+impl Machine {
+    /// The cell a `Spaces` field was written from.
+    pub fn decode_field(field: &str) -> String {
+        if field == "-" {
+            return String::new();
+        }
+        let mut cell = String::with_capacity(field.len());
+        let mut chars = field.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '_' => cell.push(' '),
+                '\\' => match chars.next() {
+                    Some('t') => cell.push('\t'),
+                    Some('n') => cell.push('\n'),
+                    Some('r') => cell.push('\r'),
+                    Some('u') => {
+                        let code: String = chars
+                            .by_ref()
+                            .skip_while(|&c| c == '{')
+                            .take_while(|&c| c != '}')
+                            .collect();
+                        cell.extend(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32));
+                    }
+                    Some(c) => cell.push(c),
+                    None => cell.push('\\'),
+                },
+                c => cell.push(c),
+            }
+        }
+        cell
+    }
+}
+
+impl RenderOptions {
+    /// Options for `awk`/`cut`/`grep` friendly output.
+    pub fn machine() -> Self {
+        RenderOptions {
+            machine: Some(Machine::Spaces),
+            color: false,
+            ..RenderOptions::new(crate::TableStyle::Simple)
+        }
+    }
+}
+
+impl Table {
+    pub(crate) fn render_machine(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        mode: Machine,
+    ) -> io::Result<()> {
+        let headers = self.columns.iter().map(|c| c.header.as_str());
+        let mut lines: Vec<Vec<Cow<str>>> = Vec::with_capacity(self.rows.len() + 1);
+        if options.show_header {
+            lines.push(headers.map(|h| field(h, mode)).collect());
+        }
+        lines.extend(
+            self.rows
+                .iter()
+                .map(|row| row.iter().map(|cell| field(cell, mode)).collect()),
+        );
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|i| {
+                lines
+                    .iter()
+                    .map(|line| display_width(&line[i]))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for line in &lines {
+            for (i, cell) in line.iter().enumerate() {
+                match mode {
+                    Machine::Tabs if i > 0 => sink.write_str("\t")?,
+                    Machine::Spaces if i > 0 => {
+                        let fill = widths[i - 1].saturating_sub(display_width(&line[i - 1]));
+                        sink.write_str(&" ".repeat(fill + 1))?;
+                    }
+                    _ => {}
+                }
+                sink.write_str(cell)?;
+            }
+            sink.end_line()?;
+        }
+        Ok(())
+    }
+}
+
+fn field(cell: &str, mode: Machine) -> Cow<'_, str> {
+    match mode {
+        Machine::Spaces if cell.is_empty() => Cow::Borrowed("-"),
+        Machine::Spaces if cell == "-" => Cow::Borrowed("\\-"),
+        Machine::Spaces if cell.contains(|c: char| c.is_whitespace() || c == '_' || c == '\\') => {
+            let mut escaped = String::with_capacity(cell.len() + 4);
+            for c in cell.chars() {
+                match c {
+                    ' ' => escaped.push('_'),
+                    '_' => escaped.push_str("\\_"),
+                    '\\' => escaped.push_str("\\\\"),
+                    '\t' => escaped.push_str("\\t"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\r' => escaped.push_str("\\r"),
+                    c if c.is_whitespace() => {
+                        escaped.push_str(&format!("\\u{{{:x}}}", u32::from(c)))
+                    }
+                    c => escaped.push(c),
+                }
+            }
+            Cow::Owned(escaped)
+        }
+        Machine::Tabs if cell.contains(['\t', '\n', '\r']) => {
+            Cow::Owned(cell.replace(['\t', '\n', '\r'], " "))
+        }
+        _ => Cow::Borrowed(cell),
+    }
+}
@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/options.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::borrow::Cow;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{ColorSpec, Machine, Table, TableStyle};
+
+/// Presentation settings that can differ between renderings of the same
+/// table, e.g. plain for logs and fancy for a terminal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub style: TableStyle,
+    pub theme: Theme,
+    /// Shrinks the longest columns until the table fits. Breakpoints,
+    /// column priorities and column paging go by this width, or by
+    /// `DEFAULT_WIDTH` when it is unset; `print`, `print_paged` and
+    /// `for_terminal` set it to the terminal's.
+    pub max_width: Option<usize>,
+    pub show_header: bool,
+    pub charset: Charset,
+    /// When false, no color changes are emitted even to color writers.
+    pub color: bool,
+    /// When false, link templates are not turned into hyperlinks.
+    pub hyperlinks: bool,
+    /// Plain output for scripts; see `RenderOptions::machine`.
+    pub machine: Option<Machine>,
+    /// Empty lines written after the table.
+    pub trailing_newlines: usize,
+    /// Drops the spaces that pad out the end of each line. On by default
+    /// for styles without a right border.
+    pub trim_trailing_spaces: bool,
+    /// Border glyphs the terminal draws wider or narrower than Unicode
+    /// says, such as `■` showing double-width in some CJK setups.
+    pub glyph_widths: Vec<(char, usize)>,
+    /// Mirrors the table for right-to-left readers: the first column is
+    /// drawn rightmost and left and right alignment swap.
+    pub right_to_left: bool,
+    /// Draws the header again, with its separators, after every this many
+    /// rows.
+    pub repeat_header_every: Option<usize>,
+}
+
+/// Width assumed for breakpoints, column priorities and column paging
+/// when `max_width` is unset, whatever the output is written to.
+pub const DEFAULT_WIDTH: usize = 80;
+
+impl RenderOptions {
+    pub fn new(style: TableStyle) -> Self {
+        RenderOptions {
+            style,
+            theme: Theme::default(),
+            max_width: None,
+            show_header: true,
+            charset: Charset::Unicode,
+            color: true,
+            hyperlinks: true,
+            machine: None,
+            trailing_newlines: 0,
+            trim_trailing_spaces: crate::render::style_config(style)
+                .is_none_or(|config| config.row.end.trim().is_empty()),
+            glyph_widths: Vec::new(),
+            right_to_left: false,
+            repeat_header_every: None,
+        }
+    }
+
+    pub fn trailing_newlines(mut self, lines: usize) -> Self {
+        self.trailing_newlines = lines;
+        self
+    }
+
+    pub fn right_to_left(mut self, mirror: bool) -> Self {
+        self.right_to_left = mirror;
+        self
+    }
+
+    pub fn glyph_width(mut self, glyph: char, width: usize) -> Self {
+        self.glyph_widths.retain(|&(existing, _)| existing != glyph);
+        self.glyph_widths.push((glyph, width));
+        self
+    }
+
+    /// Columns taken by border glyphs as drawn with this charset.
+    pub(crate) fn border_width(&self, glyphs: &str) -> usize {
+        self.charset
+            .apply(glyphs)
+            .chars()
+            .map(|c| {
+                self.glyph_widths
+                    .iter()
+                    .find(|&&(glyph, _)| glyph == c)
+                    .map_or(c.width().unwrap_or(0), |&(_, width)| width)
+            })
+            .sum()
+    }
+}
+
+impl TableStyle {
+    /// Every glyph the style's borders draw, with the columns Unicode says
+    /// it takes. Overrides go through `RenderOptions::glyph_width`.
+    pub fn glyph_widths(self) -> Vec<(char, usize)> {
+        let config = crate::style_lines(self);
+        let mut widths: Vec<(char, usize)> = Vec::new();
+        for line in [
+            &config.top,
+            &config.below_header,
+            &config.bottom,
+            &config.row,
+        ] {
+            for c in [line.begin, line.hline, line.sep, line.end]
+                .concat()
+                .chars()
+            {
+                if !widths.iter().any(|&(glyph, _)| glyph == c) {
+                    widths.push((c, c.width().unwrap_or(0)));
+                }
+            }
+        }
+        widths
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    pub header: Option<ColorSpec>,
+    pub row: Option<ColorSpec>,
+    pub border: Option<ColorSpec>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Charset {
+    #[default]
+    Unicode,
+    /// Replaces box-drawing borders with `+`, `-` and `|`, and "…" with
+    /// "...".
+    Ascii,
+}
+
+impl RenderOptions {
+    pub(crate) fn available_width(&self) -> usize {
+        self.max_width.unwrap_or(DEFAULT_WIDTH)
+    }
+}
+
+impl Charset {
+    pub(crate) fn apply(self, glyphs: &str) -> Cow<'_, str> {
+        match self {
+            Charset::Ascii if !glyphs.is_ascii() => {
+                Cow::Owned(glyphs.chars().map(ascii_glyph).collect())
+            }
+            _ => Cow::Borrowed(glyphs),
+        }
+    }
+
+    /// The truncation marker to draw; in ASCII mode a non-ASCII marker such
+    /// as "…" becomes "...".
+    pub(crate) fn marker(self, marker: &str) -> &str {
+        match self {
+            Charset::Ascii if !marker.is_ascii() => "...",
+            _ => marker,
+        }
+    }
+}
+
+fn ascii_glyph(c: char) -> char {
+    match c {
+        '─' | '═' | '━' | '■' => '-',
+        '│' | '┃' => '|',
+        c if c.is_ascii() => c,
+        _ => '+',
+    }
+}
+
+impl Table {
+    /// The options `print` uses, as a starting point for rendering the
+    /// table differently.
+    pub fn render_options(&self) -> RenderOptions {
+        let mut options = RenderOptions::new(self.style);
+        options.theme.border = self.border_color.clone();
+        options.glyph_widths = self.glyph_widths.clone();
+        if let Some(trim) = self.trim_trailing_spaces {
+            options.trim_trailing_spaces = trim;
+        }
+        options.right_to_left = self.right_to_left;
+        options.repeat_header_every = self.repeat_header_every;
+        options
+    }
+
+    /// Repeats the header every `rows` rows so long dumps keep their
+    /// context while scrolling.
+    pub fn repeat_header_every(&mut self, rows: usize) {
+        self.repeat_header_every = Some(rows);
+    }
+
+    /// See `RenderOptions::right_to_left`.
+    pub fn set_right_to_left(&mut self, mirror: bool) {
+        self.right_to_left = mirror;
+    }
+
+    /// Overrides whether lines lose their trailing spaces; see
+    /// `RenderOptions::trim_trailing_spaces`.
+    pub fn set_trim_trailing_spaces(&mut self, trim: bool) {
+        self.trim_trailing_spaces = Some(trim);
+    }
+
+    /// Sizes borders for a terminal that draws `glyph` `width` columns
+    /// wide; see `RenderOptions::glyph_width`.
+    pub fn set_glyph_width(&mut self, glyph: char, width: usize) {
+        self.glyph_widths.retain(|&(existing, _)| existing != glyph);
+        self.glyph_widths.push((glyph, width));
+    }
+}
@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/pager.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::env;
+use std::io::{self, BufWriter, IsTerminal};
+use std::process::{Command, Stdio};
+
+use terminal_size::{terminal_size, Height};
+
+use crate::{Table, TermCaps};
+
+impl Table {
+    /// Prints through `$PAGER` (or `less -R`) when stdout is a terminal and
+    /// the table is taller than the screen; prints directly otherwise.
+    pub fn print_paged(&self) -> io::Result<()> {
+        if !io::stdout().is_terminal() {
+            return self.print();
+        }
+        let Some((_, Height(screen))) = terminal_size() else {
+            return self.print();
+        };
+        if self.compute_layout().height < usize::from(screen) {
+            return self.print();
+        }
+        let pager = env::var("PAGER").ok();
+        let Some((program, args)) = pager_command(pager.as_deref()) else {
+            return self.print();
+        };
+        let Ok(mut child) = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+        else {
+            return self.print();
+        };
+        let stdin = child.stdin.take().expect("pager stdin is piped");
+        let options = self.render_options().for_terminal(&TermCaps::detect());
+        let result = self.print_ansi(&mut BufWriter::new(stdin), &options);
+        child.wait()?;
+        match result {
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        }
+    }
+}
+
+/// Splits `$PAGER` into program and arguments, defaulting to `less -R` so
+/// colors survive. An empty `$PAGER` disables paging.
+pub(crate) fn pager_command(pager: Option<&str>) -> Option<(&str, Vec<&str>)> {
+    let mut parts = pager.unwrap_or("less -R").split_whitespace();
+    let program = parts.next()?;
+    Some((program, parts.collect()))
+}
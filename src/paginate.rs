@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/paginate.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::Table;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaginationOptions {
+    pub rows_per_page: usize,
+    /// Footers on every page aggregating that page's rows, with the grand
+    /// total below them on the last page. Without it only the last page
+    /// has footers, aggregating all rows.
+    pub page_subtotals: bool,
+}
+
+impl Default for PaginationOptions {
+    fn default() -> Self {
+        PaginationOptions {
+            rows_per_page: 50,
+            page_subtotals: false,
+        }
+    }
+}
+
+impl Table {
+    /// Splits the rows into pages that keep the column widths of the whole
+    /// table, so they line up when printed one after another. The index
+    /// column, if shown, continues across pages.
+    pub fn pages(&self, options: &PaginationOptions) -> Vec<Table> {
+        let size = options.rows_per_page.max(1);
+        let count = self.rows.len().div_ceil(size).max(1);
+        let widths = self.resolve_widths(&self.render_options());
+        let total = self.footer_cells();
+        (0..count)
+            .map(|page| {
+                let first = page * size;
+                let last = (first + size).min(self.rows.len());
+                let mut table = self.select_rows(first..last);
+                table.locked_widths = Some(widths.clone());
+                if let Some(index) = &mut table.index {
+                    index.start += first;
+                }
+                let is_last = page + 1 == count;
+                if !options.page_subtotals {
+                    table.footers.clear();
+                    if is_last {
+                        table.grand_total = total.clone();
+                    }
+                } else if is_last && count > 1 {
+                    table.grand_total = total.clone();
+                }
+                table
+            })
+            .collect()
+    }
+}
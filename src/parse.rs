@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/parse.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseEnumError;
+use crate::{Alignment, TableStyle};
+
+impl TableStyle {
+    /// The kebab-case name accepted by `from_str`, e.g. `fancy-grid`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TableStyle::Simple => "simple",
+            TableStyle::Grid => "grid",
+            TableStyle::FancyGrid => "fancy-grid",
+            TableStyle::Clean => "clean",
+            TableStyle::Round => "round",
+            TableStyle::Banner => "banner",
+            TableStyle::Block => "block",
+            TableStyle::Amiga => "amiga",
+            TableStyle::Minimal => "minimal",
+            TableStyle::Compact => "compact",
+            TableStyle::Markdown => "markdown",
+            TableStyle::Dotted => "dotted",
+            TableStyle::Heavy => "heavy",
+            TableStyle::Neon => "neon",
+        }
+    }
+}
+
+impl Alignment {
+    pub const ALL: [Alignment; 3] = [Alignment::Left, Alignment::Center, Alignment::Right];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Alignment::Left => "left",
+            Alignment::Center => "center",
+            Alignment::Right => "right",
+        }
+    }
+}
+
+/// Finds the variant named `value`, ignoring case and accepting `_` for
+/// `-`.
+fn lookup<T: Copy>(
+    kind: &'static str,
+    value: &str,
+    variants: &[T],
+    name: impl Fn(T) -> &'static str,
+) -> Result<T, ParseEnumError> {
+    let wanted = value.trim().to_ascii_lowercase().replace('_', "-");
+    variants
+        .iter()
+        .copied()
+        .find(|&variant| name(variant) == wanted)
+        .ok_or_else(|| ParseEnumError {
+            kind,
+            value: value.to_string(),
+            expected: variants.iter().map(|&variant| name(variant)).collect(),
+        })
+}
+
+impl FromStr for TableStyle {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lookup("style", s, &TableStyle::ALL, TableStyle::name)
+    }
+}
+
+impl FromStr for Alignment {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lookup("alignment", s, &Alignment::ALL, Alignment::name)
+    }
+}
+
+impl fmt::Display for TableStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(feature = "clap")]
+impl clap::ValueEnum for TableStyle {
+    fn value_variants<'a>() -> &'a [Self] {
+        &TableStyle::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(self.name()))
+    }
+}
+
+#[cfg(feature = "clap")]
+impl clap::ValueEnum for Alignment {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Alignment::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(self.name()))
+    }
+}
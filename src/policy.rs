@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/policy.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use crate::Table;
+
+/// How rows whose length does not match the column count are treated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RowPolicy {
+    /// Rows must have one cell per column.
+    #[default]
+    Exact,
+    /// Short rows are padded with the default cell.
+    Pad,
+    /// Short rows are padded and extra cells are dropped.
+    PadOrTruncate,
+}
+
+/// What a table without rows renders as.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum EmptyPolicy {
+    /// Headers and borders, as for any other table.
+    #[default]
+    ShowHeaders,
+    /// A single line of text instead of the table.
+    ShowMessage(String),
+    /// No output at all.
+    PrintNothing,
+}
+
+impl Table {
+    /// Text used for cells missing from short rows; empty by default.
+    pub fn set_default_cell(&mut self, text: &str) {
+        self.default_cell = text.to_string();
+    }
+
+    /// How `add_row`, `try_add_row` and imports treat rows of the wrong
+    /// length; `Exact` by default. Applies to rows added from now on.
+    pub fn set_row_policy(&mut self, policy: RowPolicy) {
+        self.row_policy = policy;
+    }
+
+    pub fn set_empty_policy(&mut self, policy: EmptyPolicy) {
+        self.empty_policy = policy;
+    }
+
+    pub(crate) fn fit_row(&self, mut row: Vec<String>) -> Vec<String> {
+        if self.row_policy == RowPolicy::Exact {
+            return row;
+        }
+        let computed = self.columns.iter().filter(|c| c.compute.is_some()).count();
+        let plain = self.columns.len() - computed;
+        if row.len() < plain {
+            row.resize(plain, self.default_cell.clone());
+        } else if self.row_policy == RowPolicy::PadOrTruncate {
+            row.truncate(self.columns.len());
+        }
+        row
+    }
+}
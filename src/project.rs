@@ -0,0 +1,452 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/project.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    Aggregate, Alignment, Column, Distribution, DurationUnit, Format, HeaderOrientation, Locale,
+    RowPolicy, Table, TableStyle, Truncate, VerticalAlignment, Wrap,
+};
+
+const VERSION: u64 = 1;
+
+impl Table {
+    /// Saves rows, columns, style and formatting to a `.tabprinter` file.
+    /// Computed columns keep their current values but are not recomputed
+    /// after loading; validators, colors and highlights are not saved.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_project(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Opens a file written by [`Table::save`].
+    pub fn load(path: &str) -> io::Result<Table> {
+        Table::from_project_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn write_project<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, &self.project())?;
+        Ok(())
+    }
+
+    pub fn from_project_reader<R: Read>(reader: R) -> io::Result<Table> {
+        let value: Value = serde_json::from_reader(reader)?;
+        let Value::Object(project) = value else {
+            return Err(invalid("expected a JSON object"));
+        };
+        match project.get("tabprinter").and_then(Value::as_u64) {
+            Some(version) if version <= VERSION => {}
+            Some(version) => {
+                return Err(invalid(&format!("unsupported project version {}", version)))
+            }
+            None => return Err(invalid("not a tabprinter project")),
+        }
+
+        let style = match project.get("style").and_then(Value::as_str) {
+            Some(name) => name
+                .parse()
+                .map_err(|error| invalid(&format!("{}", error)))?,
+            None => TableStyle::Simple,
+        };
+        let mut table = Table::new(style);
+        for column in array(&project, "columns")? {
+            table.columns.push(column_from(column)?);
+        }
+        for (index, row) in array(&project, "rows")?.iter().enumerate() {
+            let Value::Array(cells) = row else {
+                return Err(invalid("expected every row to be an array"));
+            };
+            let row = cells
+                .iter()
+                .map(|cell| {
+                    cell.as_str().map(String::from).ok_or_else(|| {
+                        invalid(&format!("row {}: expected a string, found {}", index, cell))
+                    })
+                })
+                .collect::<io::Result<Vec<String>>>()?;
+            table.try_add_row(row)?;
+        }
+        for footer in array(&project, "footers")? {
+            let column = footer["column"]
+                .as_u64()
+                .ok_or_else(|| invalid("bad footer"))?;
+            table.set_footer(column as usize, unit(&footer["aggregate"])?)?;
+        }
+
+        table.title = project
+            .get("title")
+            .and_then(Value::as_str)
+            .map(String::from);
+        table.max_rows = number(&project, "max_rows");
+        table.max_row_height = number(&project, "max_row_height");
+        if let Some(total) = project.get("total_width").filter(|v| !v.is_null()) {
+            let width = total["width"]
+                .as_u64()
+                .ok_or_else(|| invalid("bad total_width"))?;
+            let distribution = unit(&total["distribution"])?;
+            table.total_width = Some((width as usize, distribution));
+        }
+        if let Some(value) = project.get("header_orientation") {
+            table.header_orientation = unit(value)?;
+        }
+        if let Some(value) = project.get("row_policy") {
+            table.row_policy = unit(value)?;
+        }
+        if let Some(value) = project.get("default_cell").and_then(Value::as_str) {
+            table.default_cell = value.to_string();
+        }
+        if let Some(value) = project.get("locale") {
+            table.locale = locale_from(value)?;
+        }
+        table.strict = flag(&project, "strict");
+        table.column_paging = flag(&project, "column_paging");
+        Ok(table)
+    }
+
+    fn project(&self) -> Value {
+        let footers: Vec<Value> = self
+            .footers
+            .iter()
+            .map(|(column, aggregate)| json!({ "column": column, "aggregate": name(*aggregate) }))
+            .collect();
+        json!({
+            "tabprinter": VERSION,
+            "style": self.style.name(),
+            "title": self.title,
+            "max_rows": self.max_rows,
+            "max_row_height": self.max_row_height,
+            "total_width": self.total_width.map(|(width, distribution)| {
+                json!({ "width": width, "distribution": name(distribution) })
+            }),
+            "header_orientation": name(self.header_orientation),
+            "row_policy": name(self.row_policy),
+            "default_cell": self.default_cell,
+            "locale": locale_to(self.locale),
+            "strict": self.strict,
+            "column_paging": self.column_paging,
+            "columns": self.columns.iter().map(column_to).collect::<Vec<_>>(),
+            "footers": footers,
+            "rows": self.rows,
+        })
+    }
+}
+
+/// Enums as stored in project files. The names are spelled out rather than
+/// derived from the variants, so renaming a variant does not change the
+/// file format.
+trait Named: Copy + 'static {
+    /// The variants `unit` accepts; fields of other variants are stored
+    /// separately.
+    const VARIANTS: &'static [Self];
+
+    fn name(self) -> &'static str;
+}
+
+impl Named for Aggregate {
+    const VARIANTS: &'static [Self] = &[
+        Aggregate::Sum,
+        Aggregate::Mean,
+        Aggregate::Min,
+        Aggregate::Max,
+        Aggregate::Count,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Mean => "mean",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Count => "count",
+        }
+    }
+}
+
+impl Named for Distribution {
+    const VARIANTS: &'static [Self] = &[
+        Distribution::Even,
+        Distribution::Weighted,
+        Distribution::ShrinkLongest,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Distribution::Even => "even",
+            Distribution::Weighted => "weighted",
+            Distribution::ShrinkLongest => "shrinklongest",
+        }
+    }
+}
+
+impl Named for HeaderOrientation {
+    const VARIANTS: &'static [Self] = &[
+        HeaderOrientation::Horizontal,
+        HeaderOrientation::Vertical,
+        HeaderOrientation::Auto,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            HeaderOrientation::Horizontal => "horizontal",
+            HeaderOrientation::Vertical => "vertical",
+            HeaderOrientation::Auto => "auto",
+        }
+    }
+}
+
+impl Named for RowPolicy {
+    const VARIANTS: &'static [Self] = &[RowPolicy::Exact, RowPolicy::Pad, RowPolicy::PadOrTruncate];
+
+    fn name(self) -> &'static str {
+        match self {
+            RowPolicy::Exact => "exact",
+            RowPolicy::Pad => "pad",
+            RowPolicy::PadOrTruncate => "padortruncate",
+        }
+    }
+}
+
+impl Named for VerticalAlignment {
+    const VARIANTS: &'static [Self] = &[
+        VerticalAlignment::Top,
+        VerticalAlignment::Middle,
+        VerticalAlignment::Bottom,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            VerticalAlignment::Top => "top",
+            VerticalAlignment::Middle => "middle",
+            VerticalAlignment::Bottom => "bottom",
+        }
+    }
+}
+
+impl Named for Truncate {
+    const VARIANTS: &'static [Self] = &[Truncate::Start, Truncate::Middle, Truncate::End];
+
+    fn name(self) -> &'static str {
+        match self {
+            Truncate::Start => "start",
+            Truncate::Middle => "middle",
+            Truncate::End => "end",
+        }
+    }
+}
+
+impl Named for Wrap {
+    const VARIANTS: &'static [Self] = &[Wrap::Off, Wrap::Word, Wrap::Anywhere];
+
+    fn name(self) -> &'static str {
+        match self {
+            Wrap::Off => "off",
+            Wrap::Word => "word",
+            Wrap::Anywhere => "anywhere",
+            Wrap::Hybrid { .. } => "hybrid",
+        }
+    }
+}
+
+impl Named for DurationUnit {
+    const VARIANTS: &'static [Self] = &[DurationUnit::Seconds, DurationUnit::Millis];
+
+    fn name(self) -> &'static str {
+        match self {
+            DurationUnit::Seconds => "seconds",
+            DurationUnit::Millis => "millis",
+        }
+    }
+}
+
+fn column_to(column: &Column) -> Value {
+    json!({
+        "header": column.header,
+        "width": column.width,
+        "alignment": column.alignment.name(),
+        "header_alignment": column.header_alignment.map(Alignment::name),
+        "vertical_alignment": name(column.vertical_alignment),
+        "wrap": wrap_to(column.wrap),
+        "truncate": name(column.truncate),
+        "marker": column.marker,
+        "pinned": column.pinned,
+        "priority": column.priority,
+        "signed_colors": column.signed_colors,
+        "format": format_to(column.format),
+        "locale": column.locale.map(locale_to),
+        "link_template": column.link_template,
+        "raw": column.raw,
+        "unit": column.unit,
+        "unit_in_header": column.unit_in_header,
+        "description": column.description,
+    })
+}
+
+fn column_from(value: &Value) -> io::Result<Column> {
+    let Value::Object(spec) = value else {
+        return Err(invalid("expected every column to be an object"));
+    };
+    let alignment = |key: &str| -> io::Result<Option<Alignment>> {
+        match spec.get(key).and_then(Value::as_str) {
+            Some(name) => name
+                .parse()
+                .map(Some)
+                .map_err(|error| invalid(&format!("{}", error))),
+            None => Ok(None),
+        }
+    };
+    let header = spec
+        .get("header")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let width = number(spec, "width").unwrap_or(10);
+    let mut column = Column::new(
+        header,
+        width,
+        alignment("alignment")?.unwrap_or(Alignment::Left),
+    );
+    column.header_alignment = alignment("header_alignment")?;
+    if let Some(value) = spec.get("vertical_alignment") {
+        column.vertical_alignment = unit(value)?;
+    }
+    if let Some(value) = spec.get("wrap") {
+        column.wrap = wrap_from(value)?;
+    }
+    if let Some(value) = spec.get("truncate") {
+        column.truncate = unit(value)?;
+    }
+    if let Some(marker) = spec.get("marker").and_then(Value::as_str) {
+        column.marker = marker.to_string();
+    }
+    column.pinned = flag(spec, "pinned");
+    column.priority = number(spec, "priority").map(|priority| priority as u32);
+    column.signed_colors = flag(spec, "signed_colors");
+    if let Some(value) = spec.get("format") {
+        column.format = format_from(value)?;
+    }
+    if let Some(value) = spec.get("locale").filter(|v| !v.is_null()) {
+        column.locale = Some(locale_from(value)?);
+    }
+    column.link_template = spec
+        .get("link_template")
+        .and_then(Value::as_str)
+        .map(String::from);
+    column.raw = flag(spec, "raw");
+    column.unit = spec.get("unit").and_then(Value::as_str).map(String::from);
+    column.unit_in_header = flag(spec, "unit_in_header");
+    column.description = spec
+        .get("description")
+        .and_then(Value::as_str)
+        .map(String::from);
+    Ok(column)
+}
+
+fn wrap_to(wrap: Wrap) -> Value {
+    match wrap {
+        Wrap::Hybrid { threshold } => json!({ "hybrid": threshold }),
+        other => name(other),
+    }
+}
+
+fn wrap_from(value: &Value) -> io::Result<Wrap> {
+    if let Some(threshold) = value.get("hybrid").and_then(Value::as_u64) {
+        return Ok(Wrap::Hybrid {
+            threshold: threshold as usize,
+        });
+    }
+    unit(value)
+}
+
+fn format_to(format: Format) -> Value {
+    match format {
+        Format::Plain => json!({ "kind": "plain" }),
+        Format::Percent { bar } => json!({ "kind": "percent", "bar": bar }),
+        Format::Bytes => json!({ "kind": "bytes" }),
+        Format::Count => json!({ "kind": "count" }),
+        Format::Duration { unit } => json!({ "kind": "duration", "unit": name(unit) }),
+        Format::Scientific { precision } => json!({ "kind": "scientific", "precision": precision }),
+        Format::SigFigs(digits) => json!({ "kind": "sigfigs", "digits": digits }),
+        Format::Fixed { decimals } => json!({ "kind": "fixed", "decimals": decimals }),
+    }
+}
+
+fn format_from(value: &Value) -> io::Result<Format> {
+    let size = |key: &str| {
+        value[key]
+            .as_u64()
+            .map(|n| n as usize)
+            .ok_or_else(|| invalid(&format!("format is missing '{}'", key)))
+    };
+    Ok(match value["kind"].as_str().unwrap_or_default() {
+        "plain" => Format::Plain,
+        "percent" => Format::Percent {
+            bar: value["bar"].as_bool().unwrap_or(false),
+        },
+        "bytes" => Format::Bytes,
+        "count" => Format::Count,
+        "duration" => Format::Duration {
+            unit: unit(&value["unit"])?,
+        },
+        "scientific" => Format::Scientific {
+            precision: size("precision")?,
+        },
+        "sigfigs" => Format::SigFigs(size("digits")?),
+        "fixed" => Format::Fixed {
+            decimals: size("decimals")?,
+        },
+        other => return Err(invalid(&format!("unknown format '{}'", other))),
+    })
+}
+
+fn locale_to(locale: Locale) -> Value {
+    json!({
+        "decimal": locale.decimal.to_string(),
+        "grouping": locale.grouping.map(String::from),
+    })
+}
+
+fn locale_from(value: &Value) -> io::Result<Locale> {
+    let char_at = |key: &str| value[key].as_str().and_then(|s| s.chars().next());
+    Ok(Locale {
+        decimal: char_at("decimal").ok_or_else(|| invalid("locale is missing 'decimal'"))?,
+        grouping: char_at("grouping"),
+    })
+}
+
+fn name<T: Named>(variant: T) -> Value {
+    Value::from(variant.name())
+}
+
+fn unit<T: Named>(value: &Value) -> io::Result<T> {
+    T::VARIANTS
+        .iter()
+        .copied()
+        .find(|variant| value.as_str() == Some(variant.name()))
+        .ok_or_else(|| invalid(&format!("unexpected value {}", value)))
+}
+
+fn array<'a>(project: &'a Map<String, Value>, key: &str) -> io::Result<&'a [Value]> {
+    match project.get(key) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(Value::Array(items)) => Ok(items),
+        Some(_) => Err(invalid(&format!("expected '{}' to be an array", key))),
+    }
+}
+
+fn number(object: &Map<String, Value>, key: &str) -> Option<usize> {
+    object.get(key).and_then(Value::as_u64).map(|n| n as usize)
+}
+
+fn flag(object: &Map<String, Value>, key: &str) -> bool {
+    object.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
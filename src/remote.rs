@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/remote.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::{CsvImportOptions, Table};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataFormat {
+    Csv,
+    Json,
+}
+
+impl Table {
+    pub fn from_url(url: &str, format: DataFormat) -> io::Result<Self> {
+        let response = ureq::get(url).call().map_err(io::Error::other)?;
+        let reader = response.into_reader();
+        match format {
+            DataFormat::Csv => Table::from_compressed_reader(reader, &CsvImportOptions::default()),
+            DataFormat::Json => Table::from_json_reader(reader),
+        }
+    }
+
+    /// Runs [`Table::from_url`] on a background thread. The returned future
+    /// works with any executor and never blocks the polling thread.
+    pub fn from_url_async(
+        url: &str,
+        format: DataFormat,
+    ) -> impl Future<Output = io::Result<Self>> + Send + 'static {
+        let state = Arc::new(Mutex::new(FetchState::default()));
+        let worker = Arc::clone(&state);
+        let url = url.to_string();
+        thread::spawn(move || {
+            let result = Table::from_url(&url, format);
+            let mut state = worker.lock().unwrap_or_else(|e| e.into_inner());
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        Fetch { state }
+    }
+}
+
+#[derive(Default)]
+struct FetchState {
+    result: Option<io::Result<Table>>,
+    waker: Option<Waker>,
+}
+
+struct Fetch {
+    state: Arc<Mutex<FetchState>>,
+}
+
+impl Future for Fetch {
+    type Output = io::Result<Table>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
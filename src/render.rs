@@ -0,0 +1,1156 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/render.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Write};
+#[cfg(feature = "color")]
+use termcolor::{HyperlinkSpec, WriteColor};
+
+use crate::text::{
+    display_width, group_thousands, pad, pad_measured, visible_width, with_marker, ELLIPSIS,
+};
+use crate::wrap::wrap_cell;
+use crate::{
+    Alignment, BorderKind, BorderPosition, BorderSegment, Charset, Color, ColorSpec, EmptyPolicy,
+    LineStyle, RenderOptions, Table, TableStyle, TableStyleConfig, VerticalAlignment,
+};
+
+pub(crate) trait Sink {
+    fn write_str(&mut self, s: &str) -> io::Result<()>;
+    fn end_line(&mut self) -> io::Result<()>;
+
+    fn set_color(&mut self, _spec: &ColorSpec) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Starts a hyperlink to `url`, or ends the open one for `None`.
+    fn set_link(&mut self, _url: Option<&str>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) struct PlainSink<'a>(pub(crate) &'a mut dyn Write);
+
+impl Sink for PlainSink<'_> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        writeln!(self.0)
+    }
+}
+
+pub(crate) struct FmtSink<'a>(pub(crate) &'a mut dyn fmt::Write);
+
+impl Sink for FmtSink<'_> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_str(s).map_err(io::Error::other)
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        self.0.write_char('\n').map_err(io::Error::other)
+    }
+}
+
+/// Prefixes every line written through it.
+pub(crate) struct Indented<'a, W: fmt::Write> {
+    inner: &'a mut W,
+    indent: &'a str,
+    line_start: bool,
+}
+
+impl<'a, W: fmt::Write> Indented<'a, W> {
+    pub(crate) fn new(inner: &'a mut W, indent: &'a str) -> Self {
+        Indented {
+            inner,
+            indent,
+            line_start: true,
+        }
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for Indented<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.line_start {
+                self.inner.write_str(self.indent)?;
+            }
+            self.inner.write_str(line)?;
+            self.line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "color")]
+pub(crate) struct ColorSink<'a, W: WriteColor>(pub(crate) &'a mut W);
+
+#[cfg(feature = "color")]
+impl<W: WriteColor> Sink for ColorSink<'_, W> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        writeln!(self.0)
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.0.set_color(&spec.to_termcolor())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.0.reset()
+    }
+
+    fn set_link(&mut self, url: Option<&str>) -> io::Result<()> {
+        if !self.0.supports_hyperlinks() {
+            return Ok(());
+        }
+        match url {
+            Some(url) => self.0.set_hyperlink(&HyperlinkSpec::open(url.as_bytes())),
+            None => self.0.set_hyperlink(&HyperlinkSpec::close()),
+        }
+    }
+}
+
+/// Collects rendered output as one string per line, dropping colors.
+#[derive(Default)]
+pub(crate) struct LineSink {
+    line: String,
+    pub(crate) lines: Vec<String>,
+}
+
+impl Sink for LineSink {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.line.push_str(s);
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        self.lines.push(std::mem::take(&mut self.line));
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MeasureSink {
+    line: usize,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+}
+
+impl Sink for MeasureSink {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.line += display_width(s);
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        self.width = self.width.max(self.line);
+        self.height += 1;
+        self.line = 0;
+        Ok(())
+    }
+}
+
+/// Holds back each line until it ends, then drops the spaces at its end,
+/// looking past color changes.
+struct TrimSink<'a> {
+    inner: &'a mut dyn Sink,
+    line: Vec<Op>,
+}
+
+enum Op {
+    Text(String),
+    Color(ColorSpec),
+    Reset,
+    Link(Option<String>),
+}
+
+impl<'a> TrimSink<'a> {
+    fn new(inner: &'a mut dyn Sink) -> Self {
+        TrimSink {
+            inner,
+            line: Vec::new(),
+        }
+    }
+
+    fn replay(&mut self) -> io::Result<()> {
+        for op in self.line.drain(..) {
+            match op {
+                Op::Text(text) => self.inner.write_str(&text)?,
+                Op::Color(spec) => self.inner.set_color(&spec)?,
+                Op::Reset => self.inner.reset()?,
+                Op::Link(url) => self.inner.set_link(url.as_deref())?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Passes on a last line that was never ended.
+    fn finish(mut self) -> io::Result<()> {
+        self.replay()
+    }
+}
+
+impl Sink for TrimSink<'_> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        match self.line.last_mut() {
+            Some(Op::Text(text)) => text.push_str(s),
+            _ => self.line.push(Op::Text(s.to_string())),
+        }
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        for op in self.line.iter_mut().rev() {
+            if let Op::Text(text) = op {
+                text.truncate(text.trim_end_matches(' ').len());
+                if !text.is_empty() {
+                    break;
+                }
+            }
+        }
+        self.replay()?;
+        self.inner.end_line()
+    }
+
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        self.line.push(Op::Color(spec.clone()));
+        Ok(())
+    }
+
+    fn reset(&mut self) -> io::Result<()> {
+        self.line.push(Op::Reset);
+        Ok(())
+    }
+
+    fn set_link(&mut self, url: Option<&str>) -> io::Result<()> {
+        self.line.push(Op::Link(url.map(String::from)));
+        Ok(())
+    }
+}
+
+/// Drops color changes on their way to the wrapped sink.
+struct Monochrome<'a>(&'a mut dyn Sink);
+
+impl Sink for Monochrome<'_> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_str(s)
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        self.0.end_line()
+    }
+}
+
+pub(crate) fn style_config(style: TableStyle) -> Option<&'static TableStyleConfig> {
+    match style {
+        TableStyle::Simple | TableStyle::Amiga => None,
+        style => Some(crate::style_lines(style)),
+    }
+}
+
+/// Whether the header is drawn again before the `count`th row.
+fn repeats_header(options: &RenderOptions, count: usize) -> bool {
+    options.show_header
+        && count > 0
+        && options
+            .repeat_header_every
+            .is_some_and(|every| every > 0 && count.is_multiple_of(every))
+}
+
+/// Writes frame characters in `color`, then switches back to `restore`.
+fn write_colored(
+    sink: &mut dyn Sink,
+    glyphs: &str,
+    color: Option<&ColorSpec>,
+    restore: Option<&ColorSpec>,
+) -> io::Result<()> {
+    match color {
+        Some(spec) if !glyphs.trim().is_empty() => {
+            sink.set_color(spec)?;
+            sink.write_str(glyphs)?;
+            sink.reset()?;
+            if let Some(spec) = restore {
+                sink.set_color(spec)?;
+            }
+            Ok(())
+        }
+        _ => sink.write_str(glyphs),
+    }
+}
+
+/// Compact leaves out rules whose glyphs are all empty; other styles draw
+/// them as blank lines.
+fn draws(style: TableStyle, line: &LineStyle) -> bool {
+    style != TableStyle::Compact
+        || [line.begin, line.hline, line.sep, line.end]
+            .iter()
+            .any(|glyph| !glyph.is_empty())
+}
+
+/// Lines the style draws below the last row.
+pub(crate) fn bottom_lines(style: TableStyle) -> usize {
+    style_config(style).map_or(0, |config| usize::from(draws(style, &config.bottom)))
+}
+
+/// Spaces between a cell's content and the border on either side.
+pub(crate) fn cell_padding(style: TableStyle) -> usize {
+    match style {
+        TableStyle::Compact => 0,
+        style => usize::from(style_config(style).is_some()),
+    }
+}
+
+fn mirror(alignment: Alignment) -> Alignment {
+    match alignment {
+        Alignment::Left => Alignment::Right,
+        Alignment::Right => Alignment::Left,
+        Alignment::Center => Alignment::Center,
+    }
+}
+
+impl Table {
+    pub(crate) fn render(&self, sink: &mut dyn Sink) -> io::Result<()> {
+        self.render_with(sink, &self.render_options())
+    }
+
+    pub(crate) fn render_with(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let empty = self.rows.is_empty() && options.machine.is_none();
+        let render = |sink: &mut dyn Sink| {
+            match &self.empty_policy {
+                EmptyPolicy::PrintNothing if empty => return Ok(()),
+                EmptyPolicy::ShowMessage(message) if empty => {
+                    sink.write_str(message)?;
+                    sink.end_line()?;
+                }
+                _ => self.render_table(sink, options)?,
+            }
+            for _ in 0..options.trailing_newlines {
+                sink.end_line()?;
+            }
+            Ok(())
+        };
+        if !options.trim_trailing_spaces {
+            return render(sink);
+        }
+        let mut trimmed = TrimSink::new(sink);
+        render(&mut trimmed)?;
+        trimmed.finish()
+    }
+
+    pub(crate) fn render_table(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        if let Some(mode) = options.machine {
+            return self.render_machine(sink, options, mode);
+        }
+        if let Some(index) = &self.index {
+            return self.with_index(index).render_table(sink, options);
+        }
+        if options.right_to_left {
+            let reversed: Vec<usize> = (0..self.columns.len()).rev().collect();
+            let mut mirrored = self.select_columns(&reversed);
+            for column in &mut mirrored.columns {
+                column.alignment = mirror(column.alignment);
+                column.header_alignment = column.header_alignment.map(mirror);
+            }
+            let options = RenderOptions {
+                right_to_left: false,
+                ..options.clone()
+            };
+            return mirrored.render_table(sink, &options);
+        }
+        if !self.breakpoints.is_empty() {
+            let width = options.available_width();
+            if let Some(variant) = self.breakpoint(width) {
+                if self.render_variant(sink, options, variant)? {
+                    return Ok(());
+                }
+            }
+        }
+        if self.columns.iter().any(|column| column.priority.is_some()) {
+            let width = options.available_width();
+            let hidden = self.hidden_columns(options, width);
+            if !hidden.is_empty() {
+                let shown: Vec<usize> = (0..self.columns.len())
+                    .filter(|i| !hidden.contains(i))
+                    .collect();
+                self.select_columns(&shown).render_table(sink, options)?;
+                let headers: Vec<&str> = hidden
+                    .iter()
+                    .map(|&i| self.columns[i].header.as_str())
+                    .collect();
+                sink.write_str(&format!("Hidden columns: {}", headers.join(", ")))?;
+                return sink.end_line();
+            }
+        }
+        if self.column_paging {
+            let width = options.available_width();
+            let pages = self.column_pages(width);
+            if pages.len() > 1 {
+                for (i, page) in pages.iter().enumerate() {
+                    if i > 0 {
+                        sink.end_line()?;
+                    }
+                    page.render_table(sink, options)?;
+                }
+                return Ok(());
+            }
+        }
+        let mut monochrome;
+        let sink: &mut dyn Sink = if options.color {
+            sink
+        } else {
+            monochrome = Monochrome(sink);
+            &mut monochrome
+        };
+        let widths = self.resolve_widths(options);
+        if let Some(title) = &self.title {
+            let width = self.chrome_width(options) + widths.iter().sum::<usize>();
+            sink.write_str(&pad(title, width, Alignment::Center))?;
+            sink.end_line()?;
+        }
+        let footers = self.footer_rows();
+        self.render_body(sink, options, &widths, self.visible_rows(), 0, &footers)?;
+        self.render_hidden_rows(sink, options)?;
+        self.render_legend(sink, options)
+    }
+
+    /// The rendered table, one string per line without line endings or
+    /// colors.
+    pub fn render_lines(&self) -> Vec<String> {
+        let mut sink = LineSink::default();
+        self.render(&mut sink)
+            .expect("rendering into memory cannot fail");
+        sink.lines
+    }
+
+    /// Renders the header and borders around the rows starting at
+    /// `first_row`, taking as many rows as fit into `height` lines. Column
+    /// widths are those of the whole table, so they stay put while scrolling.
+    /// The index column, right-to-left layout, column priorities and
+    /// breakpoints apply as in a full render; title and footers are left out.
+    pub fn render_viewport(&self, first_row: usize, height: usize) -> Vec<String> {
+        // The header already stays on top of every viewport.
+        let options = RenderOptions {
+            repeat_header_every: None,
+            ..self.render_options()
+        };
+        // Numbering every row first sizes the index column for the whole
+        // table too.
+        let indexed = self.index.as_ref().map(|index| self.with_index(index));
+        let table = indexed.as_ref().unwrap_or(self);
+        let widths = table.resolve_widths(&options);
+        let first_row = first_row.min(table.rows.len());
+        let render = |count: usize| {
+            let mut window = table.select_rows(first_row..first_row + count);
+            window.locked_widths = Some(widths.clone());
+            window.title = None;
+            window.footers.clear();
+            window.grand_total = None;
+            window.max_rows = None;
+            window.legend.clear();
+            let mut sink = LineSink::default();
+            window
+                .render_table(&mut sink, &options)
+                .expect("rendering into memory cannot fail");
+            sink.lines
+        };
+        // Every row takes at least one line, so at most `height` rows fit.
+        // Rendering each candidate counts the lines rows really take once
+        // wrapped and capped at the maximum row height.
+        let mut lines = render(0);
+        for count in 1..=height.min(table.rows.len() - first_row) {
+            let taller = render(count);
+            if taller.len() > height {
+                break;
+            }
+            lines = taller;
+        }
+        lines.truncate(height);
+        if options.trim_trailing_spaces {
+            for line in &mut lines {
+                line.truncate(line.trim_end_matches(' ').len());
+            }
+        }
+        lines
+    }
+
+    /// Header, the given rows and footer rows, framed by the style's
+    /// borders and laid out with precomputed widths.
+    pub(crate) fn render_body(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        widths: &[usize],
+        rows: &[Vec<String>],
+        first_row: usize,
+        footers: &[Vec<String>],
+    ) -> io::Result<()> {
+        let rows = (first_row..).zip(rows);
+        match style_config(options.style) {
+            None => self.render_simple(sink, options, widths, rows, footers),
+            Some(style) => self.render_styled(sink, options, widths, style, rows, footers),
+        }
+    }
+
+    pub(crate) fn visible_rows(&self) -> &[Vec<String>] {
+        let limit = self.max_rows.unwrap_or(usize::MAX).min(self.rows.len());
+        &self.rows[..limit]
+    }
+
+    pub(crate) fn render_hidden_rows(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        let hidden = self.rows.len() - self.visible_rows().len();
+        if hidden == 0 {
+            return Ok(());
+        }
+        let noun = if hidden == 1 { "row" } else { "rows" };
+        sink.write_str(&format!(
+            "{} and {} more {}",
+            options.charset.marker(ELLIPSIS),
+            group_thousands(hidden),
+            noun
+        ))?;
+        sink.end_line()
+    }
+
+    /// Header and row colors from the theme, falling back to the Amiga
+    /// palette for that style.
+    fn section_colors(&self, options: &RenderOptions) -> (Option<ColorSpec>, Option<ColorSpec>) {
+        let amiga = |color| {
+            let mut spec = ColorSpec::new();
+            spec.set_fg(Some(color));
+            matches!(options.style, TableStyle::Amiga).then_some(spec)
+        };
+        let header = options.theme.header.clone().or_else(|| amiga(Color::Blue));
+        let row = options.theme.row.clone().or_else(|| amiga(Color::White));
+        (header, row)
+    }
+
+    pub(crate) fn render_legend(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        if self.legend.is_empty() {
+            return Ok(());
+        }
+        let swatch = match options.charset {
+            Charset::Unicode => "■",
+            Charset::Ascii => "#",
+        };
+        for (i, (spec, label)) in self.legend.iter().enumerate() {
+            if i > 0 {
+                sink.write_str("  ")?;
+            }
+            sink.set_color(spec)?;
+            sink.write_str(swatch)?;
+            sink.reset()?;
+            sink.write_str(" ")?;
+            sink.write_str(label)?;
+        }
+        sink.end_line()
+    }
+
+    fn render_simple<'a>(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        widths: &[usize],
+        rows: impl Iterator<Item = (usize, &'a Vec<String>)>,
+        footers: &[Vec<String>],
+    ) -> io::Result<()> {
+        let (header_color, row_color) = self.section_colors(options);
+        if let Some(spec) = &header_color {
+            sink.set_color(spec)?;
+        }
+        let headers = self.headers(options.style);
+        if options.show_header {
+            self.render_cells(
+                sink,
+                options,
+                widths,
+                &headers,
+                &SIMPLE_HEADER,
+                Section::Header,
+            )?;
+        }
+
+        match &row_color {
+            Some(spec) => sink.set_color(spec)?,
+            None if header_color.is_some() => sink.reset()?,
+            None => {}
+        }
+        for (count, (index, row)) in rows.enumerate() {
+            if repeats_header(options, count) {
+                if let Some(spec) = &header_color {
+                    sink.set_color(spec)?;
+                }
+                self.render_cells(
+                    sink,
+                    options,
+                    widths,
+                    &headers,
+                    &SIMPLE_HEADER,
+                    Section::Header,
+                )?;
+                match &row_color {
+                    Some(spec) => sink.set_color(spec)?,
+                    None if header_color.is_some() => sink.reset()?,
+                    None => {}
+                }
+            }
+            self.render_cells(sink, options, widths, row, &SIMPLE_ROW, Section::Row(index))?;
+        }
+        for footer in footers {
+            self.render_cells(sink, options, widths, footer, &SIMPLE_ROW, Section::Footer)?;
+        }
+        if row_color.is_some() {
+            sink.reset()?;
+        }
+        Ok(())
+    }
+
+    fn render_styled<'a>(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        widths: &[usize],
+        style: &TableStyleConfig,
+        rows: impl Iterator<Item = (usize, &'a Vec<String>)>,
+        footers: &[Vec<String>],
+    ) -> io::Result<()> {
+        let (header_color, row_color) = self.section_colors(options);
+        let headers = self.headers(options.style);
+        let render_header = |sink: &mut dyn Sink| {
+            if let Some(spec) = &header_color {
+                sink.set_color(spec)?;
+            }
+            self.render_cells(sink, options, widths, &headers, &style.row, Section::Header)?;
+            if header_color.is_some() {
+                sink.reset()?;
+            }
+            self.render_line(
+                sink,
+                options,
+                widths,
+                &style.below_header,
+                BorderKind::BelowHeader,
+            )
+        };
+        self.render_line(sink, options, widths, &style.top, BorderKind::Top)?;
+        if options.show_header {
+            render_header(sink)?;
+        }
+        if let Some(spec) = &row_color {
+            sink.set_color(spec)?;
+        }
+        for (count, (index, row)) in rows.enumerate() {
+            if repeats_header(options, count) {
+                if row_color.is_some() {
+                    sink.reset()?;
+                }
+                self.render_line(
+                    sink,
+                    options,
+                    widths,
+                    &style.below_header,
+                    BorderKind::BelowHeader,
+                )?;
+                render_header(sink)?;
+                if let Some(spec) = &row_color {
+                    sink.set_color(spec)?;
+                }
+            }
+            self.render_cells(sink, options, widths, row, &style.row, Section::Row(index))?;
+        }
+        if row_color.is_some() {
+            sink.reset()?;
+        }
+        for footer in footers {
+            self.render_line(
+                sink,
+                options,
+                widths,
+                &style.below_header,
+                BorderKind::BelowHeader,
+            )?;
+            self.render_cells(sink, options, widths, footer, &style.row, Section::Footer)?;
+        }
+        self.render_line(sink, options, widths, &style.bottom, BorderKind::Bottom)
+    }
+
+    #[cfg(feature = "bidi")]
+    fn directional<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        crate::bidi::apply(text, self.bidi)
+    }
+
+    #[cfg(not(feature = "bidi"))]
+    fn directional<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(text)
+    }
+
+    fn headers(&self, style: TableStyle) -> Vec<String> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                if self.vertical_header(i, style) {
+                    let letters: Vec<String> = column.title().chars().map(String::from).collect();
+                    letters.join("\n")
+                } else {
+                    column.title().into_owned()
+                }
+            })
+            .collect()
+    }
+
+    fn render_line(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        widths: &[usize],
+        style: &LineStyle,
+        kind: BorderKind,
+    ) -> io::Result<()> {
+        // Columns the glyphs take beyond what Unicode says, taken back from
+        // the rule so the line keeps its length.
+        let extra = |glyph: &str| {
+            options
+                .border_width(glyph)
+                .saturating_sub(display_width(glyph))
+        };
+        if !draws(options.style, style) {
+            return Ok(());
+        }
+        let hline = options.border_width(style.hline).max(1);
+        let padding = cell_padding(options.style);
+        let mut segments = vec![(BorderPosition::Begin, style.begin.to_string())];
+        let mut excess = extra(style.begin);
+        for (i, width) in widths.iter().enumerate() {
+            if i > 0 {
+                segments.push((BorderPosition::Separator(i), style.sep.to_string()));
+                excess += extra(style.sep);
+            }
+            if i + 1 == widths.len() {
+                excess += extra(style.end);
+            }
+            let span = (width + 2 * padding).saturating_sub(excess);
+            excess -= (width + 2 * padding) - span;
+            // A wide glyph may not divide the span; spaces make up the rest.
+            let mut rule = style.hline.repeat(span / hline);
+            rule.extend(std::iter::repeat_n(' ', span % hline));
+            segments.push((BorderPosition::Rule(i), rule));
+        }
+        segments.push((BorderPosition::End, style.end.to_string()));
+        if self.border_painter.is_some() {
+            for (position, glyphs) in &segments {
+                self.paint_border(sink, options, glyphs, None, kind, *position)?;
+            }
+        } else {
+            let line: String = segments.into_iter().map(|(_, glyphs)| glyphs).collect();
+            self.write_border(sink, options, &line, None)?;
+        }
+        sink.end_line()
+    }
+
+    /// Writes frame characters in the theme's border color, then switches
+    /// back to `restore`, the color of the surrounding section.
+    fn write_border(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        glyphs: &str,
+        restore: Option<&ColorSpec>,
+    ) -> io::Result<()> {
+        let glyphs = options.charset.apply(glyphs);
+        write_colored(sink, &glyphs, options.theme.border.as_ref(), restore)
+    }
+
+    /// Like `write_border`, but lets the table's `BorderPainter` recolor
+    /// or replace the glyphs.
+    fn paint_border(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        glyphs: &str,
+        restore: Option<&ColorSpec>,
+        kind: BorderKind,
+        position: BorderPosition,
+    ) -> io::Result<()> {
+        let Some(painter) = &self.border_painter else {
+            return self.write_border(sink, options, glyphs, restore);
+        };
+        let glyphs = options.charset.apply(glyphs);
+        let segment = BorderSegment {
+            kind,
+            position,
+            glyphs: &glyphs,
+        };
+        let color = painter.color(&segment);
+        let painted = painter.glyphs(&segment);
+        let glyphs = painted.as_deref().unwrap_or(&glyphs);
+        write_colored(
+            sink,
+            glyphs,
+            color.as_ref().or(options.theme.border.as_ref()),
+            restore,
+        )
+    }
+
+    /// The wrapped and clipped lines of every cell in a row.
+    pub(crate) fn cell_lines(
+        &self,
+        widths: &[usize],
+        cells: &[impl AsRef<str>],
+        max_height: Option<usize>,
+        charset: Charset,
+    ) -> Vec<Vec<String>> {
+        cells
+            .iter()
+            .zip(&self.columns)
+            .zip(widths)
+            .map(|((cell, column), width)| {
+                let mut lines = if column.raw {
+                    cell.as_ref().split('\n').map(String::from).collect()
+                } else {
+                    wrap_cell(cell.as_ref(), *width, column, charset)
+                };
+                if let Some(max) = max_height.filter(|max| lines.len() > *max) {
+                    lines.truncate(max);
+                    if let Some(last) = lines.last_mut().filter(|_| !column.raw) {
+                        *last = with_marker(last, *width, charset.marker(&column.marker));
+                    }
+                }
+                lines
+            })
+            .collect()
+    }
+
+    /// Display column at which each cell's content area starts on a row line.
+    #[cfg(feature = "tui")]
+    pub(crate) fn cell_offsets(&self, options: &RenderOptions, widths: &[usize]) -> Vec<usize> {
+        let style = style_config(options.style).map_or(&SIMPLE_ROW, |config| &config.row);
+        let padding = cell_padding(options.style);
+        let mut offset = options.border_width(style.begin);
+        widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| {
+                if i > 0 {
+                    offset += options.border_width(style.sep);
+                }
+                let start = offset + padding;
+                offset += width + 2 * padding;
+                start
+            })
+            .collect()
+    }
+
+    fn render_cells(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        widths: &[usize],
+        cells: &[impl AsRef<str>],
+        style: &LineStyle,
+        section: Section,
+    ) -> io::Result<()> {
+        let lines = match section {
+            Section::Row(_) => self.row_lines(widths, cells, options.charset),
+            Section::Header | Section::Footer => {
+                self.cell_lines(widths, cells, None, options.charset)
+            }
+        };
+        let height = lines.iter().map(Vec::len).max().unwrap_or(1);
+        let padding = " ".repeat(cell_padding(options.style));
+        let (header_color, row_color) = self.section_colors(options);
+        let row_highlight = match section {
+            Section::Row(row) => self.row_highlights.get(&row),
+            Section::Header | Section::Footer => None,
+        };
+        let restore = match section {
+            Section::Header => header_color.as_ref(),
+            Section::Row(_) => row_highlight.or(row_color.as_ref()),
+            Section::Footer => None,
+        };
+
+        let kind = match section {
+            Section::Header => BorderKind::Header,
+            Section::Row(row) => BorderKind::Row(row),
+            Section::Footer => BorderKind::Footer,
+        };
+        if let Some(spec) = row_highlight {
+            sink.set_color(spec)?;
+        }
+        for line in 0..height {
+            self.paint_border(
+                sink,
+                options,
+                style.begin,
+                restore,
+                kind,
+                BorderPosition::Begin,
+            )?;
+            for (i, ((cell, column), width)) in
+                lines.iter().zip(&self.columns).zip(widths).enumerate()
+            {
+                if i > 0 {
+                    let position = BorderPosition::Separator(i);
+                    self.paint_border(sink, options, style.sep, restore, kind, position)?;
+                }
+                let offset = match column.vertical_alignment {
+                    VerticalAlignment::Top => 0,
+                    VerticalAlignment::Middle => (height - cell.len()) / 2,
+                    VerticalAlignment::Bottom => height - cell.len(),
+                };
+                let text = line
+                    .checked_sub(offset)
+                    .and_then(|line| cell.get(line))
+                    .map(String::as_str)
+                    .unwrap_or("");
+                let text = self.directional(text);
+                let alignment = match section {
+                    Section::Header => column.header_alignment.unwrap_or(column.alignment),
+                    Section::Row(_) | Section::Footer => column.alignment,
+                };
+                let padded = if column.raw && !matches!(section, Section::Header) {
+                    pad_measured(&text, visible_width(&text), *width, alignment)
+                } else {
+                    pad(&text, *width, alignment)
+                };
+                let cell_highlight = match section {
+                    Section::Row(row) => self.cell_highlights.get(&(row, i)),
+                    Section::Header | Section::Footer => None,
+                };
+                if let Some(spec) = cell_highlight {
+                    sink.set_color(spec)?;
+                }
+                sink.write_str(&padding)?;
+                match section {
+                    Section::Row(_) if column.raw => sink.write_str(&padded)?,
+                    Section::Row(_) => {
+                        let base = cell_highlight.or(restore);
+                        let link = column
+                            .link(cells[i].as_ref())
+                            .filter(|_| options.hyperlinks && !text.is_empty());
+                        self.write_spans(sink, base, &padded, &text, i, link.as_deref())?
+                    }
+                    Section::Header | Section::Footer => sink.write_str(&padded)?,
+                }
+                sink.write_str(&padding)?;
+                if cell_highlight.is_some() {
+                    sink.reset()?;
+                    if let Some(spec) = restore {
+                        sink.set_color(spec)?;
+                    }
+                }
+            }
+            self.paint_border(sink, options, style.end, restore, kind, BorderPosition::End)?;
+            sink.end_line()?;
+        }
+        if row_highlight.is_some() {
+            sink.reset()?;
+            if let Some(spec) = &row_color {
+                sink.set_color(spec)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Table {
+    /// Writes a padded cell, coloring the column's highlight spans and
+    /// going back to `base`, the color around the cell, after each one.
+    /// With a `link`, the text without its padding becomes a hyperlink.
+    fn write_spans(
+        &self,
+        sink: &mut dyn Sink,
+        base: Option<&ColorSpec>,
+        padded: &str,
+        text: &str,
+        column: usize,
+        link: Option<&str>,
+    ) -> io::Result<()> {
+        let spans = self.columns[column].spans(text, self.locale_of(column));
+        if spans.is_empty() && link.is_none() {
+            return sink.write_str(padded);
+        }
+        let left = padded.find(text).unwrap_or(0);
+        let right = left + text.len();
+        sink.write_str(&padded[..left])?;
+        if link.is_some() {
+            sink.set_link(link)?;
+        }
+        let mut written = left;
+        for (span, color) in spans {
+            sink.write_str(&padded[written..left + span.start])?;
+            sink.set_color(&color)?;
+            sink.write_str(&padded[left + span.start..left + span.end])?;
+            sink.reset()?;
+            if let Some(spec) = base {
+                sink.set_color(spec)?;
+            }
+            written = left + span.end;
+        }
+        sink.write_str(&padded[written..right])?;
+        if link.is_some() {
+            sink.set_link(None)?;
+        }
+        sink.write_str(&padded[right..])
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Header,
+    /// A data row and its index into the table's rows.
+    Row(usize),
+    Footer,
+}
+
+const SIMPLE_HEADER: LineStyle = LineStyle {
+    begin: "",
+    hline: "",
+    sep: " ",
+    end: "",
+};
+
+const SIMPLE_ROW: LineStyle = LineStyle {
+    begin: "",
+    hline: "",
+    sep: " ",
+    end: " ",
+};
+
+/// Lines of a rendered table, produced as they are requested; see
+/// `Table::render_iter`.
+pub struct RenderIter<'a> {
+    table: &'a Table,
+    options: RenderOptions,
+    widths: Vec<usize>,
+    rows: &'a [Vec<String>],
+    pub(crate) next_row: usize,
+    lines: VecDeque<String>,
+    finished: bool,
+}
+
+impl Table {
+    /// Renders lazily, one line per `next`. Only the column widths are
+    /// worked out up front; rows are laid out as the iterator reaches them.
+    /// Tables using breakpoints, priorities or column paging are rendered
+    /// in one go on the first call.
+    pub fn render_iter(&self) -> RenderIter<'_> {
+        let options = self.render_options();
+        let eager = !self.breakpoints.is_empty()
+            || options.right_to_left
+            || self.index.is_some()
+            || options.repeat_header_every.is_some()
+            || (self.rows.is_empty() && self.empty_policy != EmptyPolicy::ShowHeaders)
+            || self.column_paging
+            || self.columns.iter().any(|column| column.priority.is_some());
+        let mut iter = RenderIter {
+            table: self,
+            widths: Vec::new(),
+            rows: &[],
+            next_row: 0,
+            lines: VecDeque::new(),
+            finished: eager,
+            options,
+        };
+        if eager {
+            iter.lines = self.render_lines().into();
+            return iter;
+        }
+        iter.widths = self.resolve_widths(&iter.options);
+        iter.rows = self.visible_rows();
+        if let Some(title) = &self.title {
+            let width = self.chrome_width(&iter.options) + iter.widths.iter().sum::<usize>();
+            iter.lines.push_back(pad(title, width, Alignment::Center));
+        }
+        let mut head = iter.render(|sink, table, options, widths| {
+            table.render_body(sink, options, widths, &[], 0, &[])
+        });
+        head.truncate(head.len() - bottom_lines(iter.options.style));
+        iter.lines.extend(head);
+        iter
+    }
+}
+
+impl RenderIter<'_> {
+    fn render(
+        &self,
+        f: impl FnOnce(&mut LineSink, &Table, &RenderOptions, &[usize]) -> io::Result<()>,
+    ) -> Vec<String> {
+        let mut sink = LineSink::default();
+        f(&mut sink, self.table, &self.options, &self.widths)
+            .expect("rendering into memory cannot fail");
+        sink.lines
+    }
+
+    fn refill(&mut self) {
+        if let Some(row) = self.rows.get(self.next_row) {
+            let section = Section::Row(self.next_row);
+            self.next_row += 1;
+            let lines = self.render(|sink, table, options, widths| {
+                let style = style_config(options.style).map_or(&SIMPLE_ROW, |c| &c.row);
+                table.render_cells(sink, options, widths, row, style, section)
+            });
+            self.lines.extend(lines);
+            return;
+        }
+        self.finished = true;
+        let head = self
+            .render(|sink, table, options, widths| {
+                table.render_body(sink, options, widths, &[], 0, &[])
+            })
+            .len()
+            - bottom_lines(self.options.style);
+        let tail = self.render(|sink, table, options, widths| {
+            table.render_body(sink, options, widths, &[], 0, &table.footer_rows())?;
+            table.render_hidden_rows(sink, options)?;
+            table.render_legend(sink, options)
+        });
+        self.lines.extend(tail.into_iter().skip(head));
+        let blank = self.options.trailing_newlines;
+        self.lines.extend(std::iter::repeat_n(String::new(), blank));
+    }
+}
+
+impl Iterator for RenderIter<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while self.lines.is_empty() && !self.finished {
+            self.refill();
+        }
+        let line = self.lines.pop_front()?;
+        Some(match self.options.trim_trailing_spaces {
+            true => line.trim_end_matches(' ').to_string(),
+            false => line,
+        })
+    }
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/report.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::io::{self, Write};
+
+use crate::layout::shared_widths;
+use crate::render::PlainSink;
+use crate::{Alignment, RenderOptions, Table, TableStyle};
+
+/// Prints `tables` one after another with a blank line between them. With
+/// `shared_widths`, columns at the same position get the same width in
+/// every table, so sections with the same schema line up.
+#[cfg(feature = "color")]
+pub fn print_all(tables: &[&Table], shared_widths: bool) -> io::Result<()> {
+    use crate::render::ColorSink;
+    use termcolor::{BufferWriter, ColorChoice};
+
+    let stdout = BufferWriter::stdout(ColorChoice::Always);
+    let mut buffer = stdout.buffer();
+    render_all(tables, shared_widths, |table, options| {
+        table.render_with(&mut ColorSink(&mut buffer), options)
+    })?;
+    stdout.print(&buffer)
+}
+
+#[cfg(not(feature = "color"))]
+pub fn print_all(tables: &[&Table], shared_widths: bool) -> io::Result<()> {
+    let mut buffer = Vec::new();
+    write_all(&mut buffer, tables, shared_widths)?;
+    io::stdout().lock().write_all(&buffer)
+}
+
+/// Like [`print_all`], writing uncolored output to `writer`.
+pub fn write_all(writer: &mut dyn Write, tables: &[&Table], shared_widths: bool) -> io::Result<()> {
+    render_all(tables, shared_widths, |table, options| {
+        table.render_with(&mut PlainSink(&mut *writer), options)
+    })
+}
+
+fn render_all<F>(tables: &[&Table], shared: bool, mut render: F) -> io::Result<()>
+where
+    F: FnMut(&Table, &RenderOptions) -> io::Result<()>,
+{
+    let widths = if shared {
+        shared_widths(tables.iter().copied())
+    } else {
+        Vec::new()
+    };
+    for (i, &table) in tables.iter().enumerate() {
+        let options = table
+            .render_options()
+            .trailing_newlines(usize::from(i + 1 < tables.len()));
+        if shared {
+            let mut table = table.select_rows(0..table.rows.len());
+            table.widen_columns(&widths);
+            render(&table, &options)?;
+        } else {
+            render(table, &options)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes `sample`, or a small built-in table, once in every style, each
+/// under its name. Handy for a `--list-styles` flag.
+pub fn preview_styles(writer: &mut dyn Write, sample: Option<&Table>) -> io::Result<()> {
+    let default;
+    let sample = match sample {
+        Some(sample) => sample,
+        None => {
+            default = sample_table();
+            &default
+        }
+    };
+    for (i, style) in TableStyle::ALL.into_iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "{:?}:", style)?;
+        let mut options = sample.render_options();
+        options.style = style;
+        sample.render_with(&mut PlainSink(&mut *writer), &options)?;
+    }
+    Ok(())
+}
+
+fn sample_table() -> Table {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Name", 10, Alignment::Left);
+    table.add_column("Age", 5, Alignment::Right);
+    table.add_column("City", 15, Alignment::Center);
+    table.add_row(vec![
+        "Alice".to_string(),
+        "30".to_string(),
+        "New York".to_string(),
+    ]);
+    table.add_row(vec![
+        "Bob".to_string(),
+        "25".to_string(),
+        "Los Angeles".to_string(),
+    ]);
+    table
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/responsive.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::io;
+use std::ops::{Bound, Range, RangeBounds};
+
+use crate::render::Sink;
+use crate::text::{display_width, pad};
+use crate::{Alignment, ColumnRef, RenderOptions, Table, TableError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutVariant {
+    Full,
+    /// One `Header: value` line per cell, with rows separated by blank lines.
+    Records,
+    Hide(Vec<ColumnRef>),
+}
+
+pub(crate) type Breakpoints = Vec<(Range<usize>, LayoutVariant)>;
+
+impl Table {
+    /// Renders the table as `variant` whenever the render options'
+    /// `max_width` (see there for the default) falls into `widths`.
+    /// The first matching breakpoint wins; without one the full table is
+    /// shown.
+    pub fn add_breakpoint(
+        &mut self,
+        widths: impl RangeBounds<usize>,
+        variant: LayoutVariant,
+    ) -> Result<(), TableError> {
+        if let LayoutVariant::Hide(columns) = &variant {
+            for column in columns {
+                self.column_index(column.clone())?;
+            }
+        }
+        let start = match widths.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match widths.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => usize::MAX,
+        };
+        self.breakpoints.push((start..end, variant));
+        Ok(())
+    }
+
+    pub(crate) fn breakpoint(&self, width: usize) -> Option<&LayoutVariant> {
+        self.breakpoints
+            .iter()
+            .find(|(widths, _)| widths.contains(&width))
+            .map(|(_, variant)| variant)
+    }
+
+    /// Renders `variant`, or returns false for [`LayoutVariant::Full`].
+    pub(crate) fn render_variant(
+        &self,
+        sink: &mut dyn Sink,
+        options: &RenderOptions,
+        variant: &LayoutVariant,
+    ) -> io::Result<bool> {
+        match variant {
+            LayoutVariant::Full => return Ok(false),
+            LayoutVariant::Records => self.render_records(sink)?,
+            LayoutVariant::Hide(columns) => {
+                let hidden: Vec<usize> = columns
+                    .iter()
+                    .filter_map(|column| self.column_index(column.clone()).ok())
+                    .collect();
+                let shown: Vec<usize> = (0..self.columns.len())
+                    .filter(|i| !hidden.contains(i))
+                    .collect();
+                let mut table = self.select_columns(&shown);
+                table.breakpoints.clear();
+                table.render_table(sink, options)?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn render_records(&self, sink: &mut dyn Sink) -> io::Result<()> {
+        if let Some(title) = &self.title {
+            sink.write_str(title)?;
+            sink.end_line()?;
+        }
+        let label_width = self
+            .columns
+            .iter()
+            .map(|column| display_width(&column.title()))
+            .max()
+            .unwrap_or(0);
+        let indent = " ".repeat(label_width + 2);
+        for (i, row) in self.visible_rows().iter().enumerate() {
+            if i > 0 {
+                sink.end_line()?;
+            }
+            for (column, cell) in self.columns.iter().zip(row) {
+                sink.write_str(&pad(&column.title(), label_width, Alignment::Left))?;
+                sink.write_str(": ")?;
+                for (n, line) in cell.split('\n').enumerate() {
+                    if n > 0 {
+                        sink.end_line()?;
+                        sink.write_str(&indent)?;
+                    }
+                    sink.write_str(line)?;
+                }
+                sink.end_line()?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/row_view.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::cmp::Ordering;
+
+use crate::{Column, ColumnRef, Locale, Table, TableError};
+
+/// A row as seen by filter, sort and computed-column closures, with cells
+/// looked up by header instead of by position.
+#[derive(Clone, Copy)]
+pub struct RowView<'a> {
+    columns: &'a [Column],
+    cells: &'a [String],
+    locale: Locale,
+}
+
+/// One cell of a [`RowView`].
+#[derive(Clone, Copy)]
+pub struct CellView<'a> {
+    column: &'a Column,
+    text: &'a str,
+    locale: Locale,
+}
+
+impl<'a> RowView<'a> {
+    pub(crate) fn new(columns: &'a [Column], cells: &'a [String], locale: Locale) -> Self {
+        RowView {
+            columns,
+            cells,
+            locale,
+        }
+    }
+
+    /// `None` when the column does not exist, its name is ambiguous, or,
+    /// inside a computed column, it lies to the right of the one computed.
+    pub fn get(&self, column: impl Into<ColumnRef>) -> Option<CellView<'a>> {
+        let index = match column.into() {
+            ColumnRef::Index(index) => index,
+            ColumnRef::Name(name) => {
+                let mut matches = self
+                    .visible()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| column.header == name);
+                match (matches.next(), matches.next()) {
+                    (Some((index, _)), None) => index,
+                    _ => return None,
+                }
+            }
+        };
+        let column = self.visible().get(index)?;
+        Some(CellView {
+            column,
+            text: &self.cells[index],
+            locale: column.locale.unwrap_or(self.locale),
+        })
+    }
+
+    /// Only the columns this row has cells for.
+    fn visible(&self) -> &'a [Column] {
+        &self.columns[..self.cells.len().min(self.columns.len())]
+    }
+
+    pub fn get_str(&self, column: impl Into<ColumnRef>) -> Option<&'a str> {
+        self.get(column).map(|cell| cell.text)
+    }
+
+    pub fn get_i64(&self, column: impl Into<ColumnRef>) -> Option<i64> {
+        self.get(column)?.as_i64()
+    }
+
+    pub fn get_f64(&self, column: impl Into<ColumnRef>) -> Option<f64> {
+        self.get(column)?.as_f64()
+    }
+
+    pub fn cells(&self) -> &'a [String] {
+        self.cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = CellView<'a>> + 'a {
+        let locale = self.locale;
+        self.columns
+            .iter()
+            .zip(self.cells)
+            .map(move |(column, text)| CellView {
+                column,
+                text,
+                locale: column.locale.unwrap_or(locale),
+            })
+    }
+}
+
+impl<'a> CellView<'a> {
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn header(&self) -> &'a str {
+        &self.column.header
+    }
+
+    /// The cell as a whole number, honoring the column's locale.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.locale.parse_integer(self.text)
+    }
+
+    /// The cell as a number, read through the column's format and locale,
+    /// so `2 MiB` in a bytes column is `2097152.0`.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.column.format.value(self.text, self.locale)
+    }
+}
+
+/// A type [`Table::column_values`] can read cells as.
+pub trait FromCell: Sized {
+    /// Completes "expected ..." in the error for a cell that does not parse.
+    const EXPECTED: &'static str;
+
+    fn from_cell(cell: &CellView) -> Option<Self>;
+}
+
+impl FromCell for String {
+    const EXPECTED: &'static str = "text";
+
+    fn from_cell(cell: &CellView) -> Option<Self> {
+        Some(cell.text.to_string())
+    }
+}
+
+impl FromCell for i64 {
+    const EXPECTED: &'static str = "a whole number";
+
+    fn from_cell(cell: &CellView) -> Option<Self> {
+        cell.as_i64()
+    }
+}
+
+impl FromCell for f64 {
+    const EXPECTED: &'static str = "a number";
+
+    fn from_cell(cell: &CellView) -> Option<Self> {
+        cell.as_f64()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromCell for chrono::NaiveDate {
+    const EXPECTED: &'static str = "a date like 2024-01-31";
+
+    fn from_cell(cell: &CellView) -> Option<Self> {
+        cell.text.trim().parse().ok()
+    }
+}
+
+impl Table {
+    /// Every cell of `column` parsed as `T`, e.g.
+    /// `table.column_values::<i64>("Age")`. Fails on the first cell that
+    /// does not parse.
+    pub fn column_values<T: FromCell>(
+        &self,
+        column: impl Into<ColumnRef>,
+    ) -> Result<Vec<T>, TableError> {
+        let column = self.column_index(column)?;
+        self.row_views()
+            .enumerate()
+            .map(|(row, view)| {
+                let cell = view.get(column).expect("rows span every column");
+                T::from_cell(&cell).ok_or_else(|| TableError::InvalidCell {
+                    row,
+                    column,
+                    value: cell.text.to_string(),
+                    reason: format!("expected {}", T::EXPECTED),
+                })
+            })
+            .collect()
+    }
+
+    pub fn row_view(&self, row: usize) -> Option<RowView<'_>> {
+        let cells = self.rows.get(row)?;
+        Some(RowView::new(&self.columns, cells, self.locale))
+    }
+
+    pub fn row_views(&self) -> impl Iterator<Item = RowView<'_>> {
+        self.rows
+            .iter()
+            .map(|row| RowView::new(&self.columns, row, self.locale))
+    }
+
+    /// A copy holding only the rows for which `keep` returns true.
+    pub fn filter_rows<F>(&self, mut keep: F) -> Table
+    where
+        F: FnMut(&RowView) -> bool,
+    {
+        let indices: Vec<usize> = (0..self.rows.len())
+            .filter(|&i| keep(&RowView::new(&self.columns, &self.rows[i], self.locale)))
+            .collect();
+        self.select_rows(indices)
+    }
+
+    /// Sorts rows with a comparator that sees whole rows.
+    pub fn sort_rows_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&RowView, &RowView) -> Ordering,
+    {
+        let view = |i: usize| RowView::new(&self.columns, &self.rows[i], self.locale);
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|&a, &b| compare(&view(a), &view(b)));
+        self.reorder_rows(&order);
+    }
+}
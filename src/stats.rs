@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/stats.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::view::remap_cells;
+use crate::{Format, Locale, Table};
+
+/// Everything about a column that changes how wide its cells display: the
+/// format, the locale, raw mode and a unit shown after each value.
+type WidthKey = (Format, Locale, bool, Option<String>);
+
+/// Widths of the formatted cells in each column, so repeated renders of a
+/// growing table only measure the rows added since the last one.
+#[derive(Default)]
+pub(crate) struct ColumnStats {
+    /// Per column, how many cells have each width.
+    widths: Vec<BTreeMap<usize, usize>>,
+    /// Rows measured, counted from the top.
+    rows: usize,
+    /// What the widths were measured with; any change means starting over.
+    key: Vec<WidthKey>,
+}
+
+impl ColumnStats {
+    fn add(&mut self, widths: impl Iterator<Item = usize>) {
+        for (counts, width) in self.widths.iter_mut().zip(widths) {
+            *counts.entry(width).or_default() += 1;
+        }
+        self.rows += 1;
+    }
+
+    fn remove(&mut self, widths: impl Iterator<Item = usize>) {
+        for (counts, width) in self.widths.iter_mut().zip(widths) {
+            if let Some(count) = counts.get_mut(&width) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&width);
+                }
+            }
+        }
+        self.rows -= 1;
+    }
+}
+
+/// Behind a lock so `&Table` rendering can fill it in while tables stay
+/// `Sync`.
+#[derive(Default)]
+pub(crate) struct StatsCache(Mutex<ColumnStats>);
+
+impl StatsCache {
+    fn lock(&self) -> MutexGuard<'_, ColumnStats> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Table {
+    /// Widest formatted cell of `column` among the visible rows.
+    pub(crate) fn widest_cell(&self, column: usize) -> usize {
+        let mut stats = self.stats.lock();
+        let key: Vec<WidthKey> = (0..self.columns.len())
+            .map(|i| {
+                let column = &self.columns[i];
+                (
+                    column.format,
+                    self.locale_of(i),
+                    column.raw,
+                    column.unit.clone().filter(|_| !column.unit_in_header),
+                )
+            })
+            .collect();
+        let visible = self.visible_rows();
+        // Rows beyond a lowered `max_rows` cannot be taken back out.
+        if stats.key != key || stats.rows > visible.len() {
+            *stats = ColumnStats {
+                widths: vec![BTreeMap::new(); key.len()],
+                rows: 0,
+                key,
+            };
+        }
+        for row in &visible[stats.rows..] {
+            stats.add(self.cell_widths(row));
+        }
+        stats.widths[column]
+            .last_key_value()
+            .map_or(0, |(&width, _)| width)
+    }
+
+    /// Removes and returns row `index`, keeping the cached column widths
+    /// current without measuring the other rows again.
+    pub fn remove_row(&mut self, index: usize) -> Vec<String> {
+        assert!(index < self.rows.len(), "Row index out of range");
+        let widths: Vec<usize> = self.cell_widths(&self.rows[index]).collect();
+        let stats = self
+            .stats
+            .0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if index < stats.rows {
+            stats.remove(widths.into_iter());
+        }
+        // Rows below the removed one move up by one.
+        let shift = |row: usize| (row != index).then(|| row - usize::from(row > index));
+        self.row_highlights = self
+            .row_highlights
+            .drain()
+            .filter_map(|(row, spec)| Some((shift(row)?, spec)))
+            .collect();
+        self.cell_highlights = remap_cells(&self.cell_highlights, |row, column| {
+            Some((shift(row)?, column))
+        });
+        self.cell_meta = remap_cells(&self.cell_meta, |row, column| Some((shift(row)?, column)));
+        self.rows.remove(index)
+    }
+
+    /// Puts `row` in place of row `index` and returns the old one, keeping
+    /// the cached widths current like `remove_row` does.
+    pub(crate) fn replace_row(&mut self, index: usize, row: Vec<String>) -> Vec<String> {
+        let old: Vec<usize> = self.cell_widths(&self.rows[index]).collect();
+        let new: Vec<usize> = self.cell_widths(&row).collect();
+        let stats = self
+            .stats
+            .0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if index < stats.rows {
+            stats.remove(old.into_iter());
+            stats.add(new.into_iter());
+        }
+        std::mem::replace(&mut self.rows[index], row)
+    }
+
+    /// For changes to rows or columns other than appending rows,
+    /// `remove_row` or `replace_row`.
+    pub(crate) fn invalidate_stats(&mut self) {
+        *self
+            .stats
+            .0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner) = ColumnStats::default();
+    }
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/term.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::env;
+use std::io::{self, IsTerminal};
+
+use terminal_size::{terminal_size, Height, Width};
+
+use crate::{Charset, RenderOptions};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// What the terminal on stdout can show, as far as the environment tells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TermCaps {
+    pub is_terminal: bool,
+    pub colors: ColorDepth,
+    pub unicode: bool,
+    pub hyperlinks: bool,
+    /// Columns and rows, when stdout is a terminal.
+    pub size: Option<(usize, usize)>,
+}
+
+impl TermCaps {
+    /// Probes stdout and the usual variables: `NO_COLOR`, `CLICOLOR_FORCE`,
+    /// `COLORTERM`, `TERM`, `TERM_PROGRAM` and the locale.
+    pub fn detect() -> Self {
+        let is_terminal = io::stdout().is_terminal();
+        let size = terminal_size()
+            .filter(|_| is_terminal)
+            .map(|(Width(w), Height(h))| (usize::from(w), usize::from(h)));
+        TermCaps::from_env(is_terminal, size, |name| env::var(name).ok())
+    }
+
+    pub(crate) fn from_env(
+        is_terminal: bool,
+        size: Option<(usize, usize)>,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let var = |name: &str| lookup(name).filter(|value| !value.is_empty());
+        let term = var("TERM").unwrap_or_default();
+        let forced = var("CLICOLOR_FORCE").is_some_and(|value| value != "0");
+        let colors = if var("NO_COLOR").is_some() || (!is_terminal && !forced) || term == "dumb" {
+            ColorDepth::None
+        } else if var("COLORTERM").is_some_and(|value| value == "truecolor" || value == "24bit") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(var)
+            .unwrap_or_default()
+            .to_lowercase();
+        let unicode = locale.contains("utf-8")
+            || locale.contains("utf8")
+            || (cfg!(windows) && var("WT_SESSION").is_some());
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        let hyperlinks = is_terminal
+            && (matches!(
+                program.as_str(),
+                "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+            ) || ["kitty", "foot", "alacritty"]
+                .iter()
+                .any(|name| term.contains(name))
+                || var("WT_SESSION").is_some()
+                || var("VTE_VERSION")
+                    .and_then(|version| version.parse::<u32>().ok())
+                    .is_some_and(|version| version >= 5000));
+        TermCaps {
+            is_terminal,
+            colors,
+            unicode,
+            hyperlinks,
+            size,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Falls back to ASCII borders, plain text and no links where the
+    /// terminal lacks them, and fits the table to its width unless
+    /// `max_width` is already set.
+    pub fn for_terminal(mut self, caps: &TermCaps) -> Self {
+        if !caps.unicode {
+            self.charset = Charset::Ascii;
+        }
+        self.color &= caps.colors != ColorDepth::None;
+        self.hyperlinks &= caps.hyperlinks;
+        if self.max_width.is_none() {
+            self.max_width = caps.size.map(|(width, _)| width);
+        }
+        self
+    }
+}
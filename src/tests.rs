@@ -42,6 +42,7 @@ fn test_add_column() {
     assert!(matches!(table.columns[0].alignment, Alignment::Left));
 }
 
+#[cfg(feature = "csv")]
 #[test]
 fn test_csv_usage() {
     let table = Table::from_csv("examples/data.csv").unwrap();
@@ -65,6 +66,7 @@ fn test_add_row_mismatch() {
     table.add_row(vec!["Value1".to_string(), "Value2".to_string()]);
 }
 
+#[cfg(feature = "color")]
 #[test]
 fn test_print_color() {
     let table = create_test_table(TableStyle::Grid);
@@ -82,3 +84,3244 @@ fn test_print_to_writer() {
     let result = String::from_utf8(buffer).unwrap();
     assert!(!result.is_empty());
 }
+
+#[test]
+fn test_sort_by_cached_key() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec![
+        "Carol".to_string(),
+        "7".to_string(),
+        "Chicago".to_string(),
+    ]);
+    table
+        .sort_by_cached_key(1, |cell| cell.parse::<u32>().unwrap_or(0))
+        .unwrap();
+    let ages: Vec<&str> = table.rows.iter().map(|r| r[1].as_str()).collect();
+    assert_eq!(ages, vec!["7", "25", "30"]);
+}
+
+#[test]
+fn test_sort_by_cached_key_out_of_range() {
+    let mut table = create_test_table(TableStyle::Simple);
+    assert_eq!(
+        table.sort_by_cached_key(3, |cell| cell.to_string()),
+        Err(TableError::ColumnOutOfRange(3))
+    );
+}
+
+#[test]
+fn test_compute_layout_grid() {
+    let table = create_test_table(TableStyle::Grid);
+    let layout = table.compute_layout();
+    assert_eq!(layout.column_widths, vec![8, 5, 13]);
+    assert_eq!(
+        layout.total_width,
+        1 + (8 + 2) + 1 + (5 + 2) + 1 + (13 + 2) + 1
+    );
+    assert_eq!(layout.height, 6);
+}
+
+#[test]
+fn test_compute_layout_matches_output() {
+    let mut table = create_test_table(TableStyle::Heavy);
+    table.add_row(vec![
+        "Maximilian".to_string(),
+        "41".to_string(),
+        "Saint Petersburg".to_string(),
+    ]);
+    let layout = table.compute_layout();
+    assert_eq!(layout.column_widths, vec![10, 5, 16]);
+
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    assert_eq!(output.lines().count(), layout.height);
+    assert!(output
+        .lines()
+        .all(|line| line.chars().count() == layout.total_width));
+}
+
+#[test]
+fn test_set_total_width_is_exact() {
+    for style in [TableStyle::Simple, TableStyle::Grid, TableStyle::Block] {
+        for distribution in [
+            Distribution::Even,
+            Distribution::Weighted,
+            Distribution::ShrinkLongest,
+        ] {
+            for width in [24, 40, 80] {
+                let mut table = create_test_table(style);
+                table.set_total_width(width, distribution);
+                table.set_trim_trailing_spaces(false);
+                let mut buffer = Vec::new();
+                table.print_to_writer(&mut buffer).unwrap();
+                let output = String::from_utf8(buffer).unwrap();
+                let widest = output.lines().map(|l| l.chars().count()).max();
+                assert_eq!(widest, Some(width), "{:?} {:?}", style, distribution);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_shrink_longest_truncates_widest_column() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.set_total_width(30, Distribution::ShrinkLongest);
+    let layout = table.compute_layout();
+    assert_eq!(layout.column_widths, vec![7, 5, 8]);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    assert!(String::from_utf8(buffer).unwrap().contains("Los Ang…"));
+}
+
+#[test]
+fn test_wrap_modes() {
+    let text = "see https://example.com/x";
+    assert_eq!(
+        wrap::wrap(text, 10, Wrap::Word),
+        vec!["see", "https://ex", "ample.com/", "x"]
+    );
+    assert_eq!(
+        wrap::wrap(text, 10, Wrap::Anywhere),
+        vec!["see https:", "//example.", "com/x"]
+    );
+    assert_eq!(
+        wrap::wrap(text, 10, Wrap::Hybrid { threshold: 8 }),
+        vec!["see https:", "//example.", "com/x"]
+    );
+    assert_eq!(
+        wrap::wrap("the quick brown fox", 10, Wrap::Hybrid { threshold: 8 }),
+        vec!["the quick", "brown fox"]
+    );
+}
+
+#[test]
+fn test_wrapped_column_renders_multiple_lines() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Id", 2, Alignment::Left);
+    table.add_column("Note", 9, Alignment::Left);
+    table.column_mut(1).unwrap().wrap(Wrap::Word);
+    table.add_row(vec!["1".to_string(), "the quick brown fox".to_string()]);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[3], "| 1  | the quick |");
+    assert_eq!(lines[4], "|    | brown fox |");
+    assert_eq!(table.compute_layout().height, 6);
+}
+
+#[cfg(feature = "bidi")]
+#[test]
+fn test_bidi_reorder_keeps_padding() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("City", 6, Alignment::Left);
+    table.add_row(vec!["שלום".to_string()]);
+    table.set_bidi(Bidi::Reorder);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    assert_eq!(output.lines().nth(3), Some("| םולש   |"));
+}
+
+#[test]
+fn test_vertical_headers_auto() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Algorithm", 1, Alignment::Left);
+    table.add_column("Fast", 1, Alignment::Center);
+    table.add_column("Stable", 1, Alignment::Center);
+    table.add_row(vec![
+        "quicksort".to_string(),
+        "y".to_string(),
+        "n".to_string(),
+    ]);
+    table.set_header_orientation(HeaderOrientation::Auto);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[1], "| Algorithm | F | S |");
+    assert_eq!(lines[2], "|           | a | t |");
+    assert_eq!(lines[6], "|           |   | e |");
+    assert_eq!(lines[8], "| quicksort | y | n |");
+    assert_eq!(table.compute_layout().column_widths, vec![9, 1, 1]);
+}
+
+#[test]
+fn test_vertical_alignment() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Top", 3, Alignment::Left);
+    table.add_column("Mid", 3, Alignment::Left);
+    table.add_column("Bot", 3, Alignment::Left);
+    table.add_column("Text", 4, Alignment::Left);
+    table
+        .column_mut(1)
+        .unwrap()
+        .vertical_alignment(VerticalAlignment::Middle);
+    table
+        .column_mut(2)
+        .unwrap()
+        .vertical_alignment(VerticalAlignment::Bottom);
+    table.add_row(vec![
+        "a".to_string(),
+        "b".to_string(),
+        "c".to_string(),
+        "1\n2\n3".to_string(),
+    ]);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[3], "| a   |     |     | 1    |");
+    assert_eq!(lines[4], "|     | b   |     | 2    |");
+    assert_eq!(lines[5], "|     |     | c   | 3    |");
+}
+
+#[test]
+fn test_max_row_height_marks_overflow() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Log", 8, Alignment::Left);
+    table.column_mut(0).unwrap().wrap(Wrap::Word);
+    table.add_row(vec!["one two three four five six".to_string()]);
+    table.set_max_row_height(2);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[3], "| one two  |");
+    assert_eq!(lines[4], "| three…   |");
+    assert_eq!(lines.len(), 6);
+}
+
+#[test]
+fn test_max_rows_footer() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("N", 6, Alignment::Right);
+    for i in 0..3494 {
+        table.add_row(vec![i.to_string()]);
+    }
+    table.set_max_rows(2);
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[3], "… and 3,492 more rows");
+}
+
+#[test]
+fn test_group_thousands() {
+    assert_eq!(text::group_thousands(0), "0");
+    assert_eq!(text::group_thousands(999), "999");
+    assert_eq!(text::group_thousands(1000), "1,000");
+    assert_eq!(text::group_thousands(1234567), "1,234,567");
+}
+
+fn numbered_table(rows: usize) -> Table {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("N", 4, Alignment::Right);
+    for i in 0..rows {
+        table.add_row(vec![i.to_string()]);
+    }
+    table
+}
+
+#[test]
+fn test_head_and_tail() {
+    let table = numbered_table(5);
+    let head = table.head(2);
+    let tail = table.tail(2);
+    assert_eq!(head.rows, vec![vec!["0"], vec!["1"]]);
+    assert_eq!(tail.rows, vec![vec!["3"], vec!["4"]]);
+    assert_eq!(table.head(10).rows.len(), 5);
+    assert_eq!(table.rows.len(), 5);
+}
+
+#[test]
+fn test_sample_is_reproducible_and_ordered() {
+    let table = numbered_table(100);
+    let first = table.sample(10, 42);
+    let second = table.sample(10, 42);
+    assert_eq!(first.rows, second.rows);
+    assert_eq!(first.rows.len(), 10);
+    let values: Vec<usize> = first.rows.iter().map(|r| r[0].parse().unwrap()).collect();
+    assert!(values.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(table.sample(200, 1).rows.len(), 100);
+}
+
+fn log_table() -> Table {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Host", 8, Alignment::Left);
+    table.add_column("Level", 6, Alignment::Left);
+    for (host, level) in [
+        ("web1", "warn"),
+        ("web2", "error"),
+        ("web1", "warn"),
+        ("web1", "info"),
+        ("web2", "error"),
+    ] {
+        table.add_row(vec![host.to_string(), level.to_string()]);
+    }
+    table
+}
+
+#[test]
+fn test_dedup() {
+    let mut table = log_table();
+    table.dedup();
+    assert_eq!(
+        table.rows,
+        vec![
+            vec!["web1", "warn"],
+            vec!["web2", "error"],
+            vec!["web1", "info"]
+        ]
+    );
+}
+
+#[test]
+fn test_dedup_by_column_with_count() {
+    let mut table = log_table();
+    table.dedup_by_column_with_count(0, "count").unwrap();
+    assert_eq!(table.columns.len(), 3);
+    assert_eq!(table.columns[2].header, "count");
+    assert_eq!(
+        table.rows,
+        vec![vec!["web1", "warn", "3"], vec!["web2", "error", "2"]]
+    );
+}
+
+#[test]
+fn test_value_counts() {
+    let table = log_table();
+    let counts = table.value_counts(1).unwrap();
+    assert_eq!(counts.columns[0].header, "Level");
+    assert_eq!(counts.columns[1].header, "count");
+    assert_eq!(
+        counts.rows,
+        vec![vec!["warn", "2"], vec!["error", "2"], vec!["info", "1"]]
+    );
+}
+
+#[test]
+fn test_split_by() {
+    let table = log_table();
+    let groups = table.split_by(0).unwrap();
+    let keys: Vec<&str> = groups.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["web1", "web2"]);
+    assert_eq!(groups[0].1.rows.len(), 3);
+    assert_eq!(groups[1].1.rows.len(), 2);
+
+    let mut buffer = Vec::new();
+    groups[1].1.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    assert_eq!(output.lines().next(), Some("  Host: web2"));
+}
+
+fn sales_table() -> Table {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Item", 6, Alignment::Left);
+    table.add_column("Amount", 6, Alignment::Right);
+    for (item, amount) in [("a", "1,200"), ("b", ""), ("c", "300.5"), ("d", " 99 ")] {
+        table.add_row(vec![item.to_string(), amount.to_string()]);
+    }
+    table
+}
+
+#[test]
+fn test_aggregate() {
+    let table = sales_table();
+    assert_eq!(table.aggregate(1, Aggregate::Sum), Ok(1599.5));
+    assert_eq!(table.aggregate(1, Aggregate::Count), Ok(3.0));
+    assert_eq!(table.aggregate(1, Aggregate::Min), Ok(99.0));
+    assert_eq!(table.aggregate(1, Aggregate::Max), Ok(1200.0));
+    assert!((table.aggregate(1, Aggregate::Mean).unwrap() - 533.1666).abs() < 0.001);
+    assert_eq!(
+        table.aggregate(0, Aggregate::Sum),
+        Err(TableError::NotNumeric {
+            row: 0,
+            column: 0,
+            value: "a".to_string()
+        })
+    );
+    assert_eq!(
+        table.aggregate(5, Aggregate::Sum),
+        Err(TableError::ColumnOutOfRange(5))
+    );
+}
+
+#[test]
+fn test_footer_aggregate() {
+    let mut table = sales_table();
+    table.set_footer(1, Aggregate::Sum).unwrap();
+    let mut buffer = Vec::new();
+    table.print_to_writer(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[7], "+--------+--------+");
+    assert_eq!(lines[8], "|        | 1599.5 |");
+}
+
+#[test]
+fn test_footer_keeps_precision_and_marks_errors() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Price", 6, Alignment::Right);
+    table.add_column("Qty", 4, Alignment::Right);
+    table.add_column("Note", 4, Alignment::Left);
+    for (price, qty) in [("1.250", "1"), ("2", "2"), ("0.125", "4")] {
+        table.add_row(vec![price.to_string(), qty.to_string(), "x".to_string()]);
+    }
+    table.set_footer("Price", Aggregate::Sum).unwrap();
+    table.set_footer("Qty", Aggregate::Mean).unwrap();
+    table.set_footer("Note", Aggregate::Sum).unwrap();
+    assert_eq!(table.footer_cell(0).as_deref(), Some("3.375"));
+    assert_eq!(table.footer_cell(1).as_deref(), Some("2.33"));
+    assert_eq!(table.footer_cell(2).as_deref(), Some(FOOTER_ERROR));
+
+    table.set_footer("Qty", Aggregate::Max).unwrap();
+    table
+        .column_mut("Qty")
+        .unwrap()
+        .format(Format::Fixed { decimals: 2 });
+    assert_eq!(table.footer_cell(1).as_deref(), Some("4.00"));
+}
+
+#[test]
+fn test_computed_column_applies_to_later_rows() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Price", 6, Alignment::Right);
+    table.add_column("Qty", 4, Alignment::Right);
+    table.add_row(vec!["2.5".to_string(), "4".to_string()]);
+    table.add_computed_column("Total", |row| {
+        let price: f64 = row[0].parse().unwrap_or(0.0);
+        let qty: f64 = row[1].parse().unwrap_or(0.0);
+        format!("{}", price * qty)
+    });
+    table.add_row(vec!["3".to_string(), "3".to_string()]);
+    table.add_row(vec!["1".to_string(), "2".to_string(), "stale".to_string()]);
+    assert_eq!(
+        table.rows,
+        vec![
+            vec!["2.5", "4", "10"],
+            vec!["3", "3", "9"],
+            vec!["1", "2", "2"]
+        ]
+    );
+}
+
+#[test]
+fn test_drop_column_left_of_computed_column_is_rejected() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.add_computed_column("Next", |row| {
+        (row[1].parse::<u32>().unwrap_or(0) + 1).to_string()
+    });
+    assert_eq!(
+        table.drop_column("Name"),
+        Err(TableError::ColumnInUse {
+            column: 0,
+            computed: 3
+        })
+    );
+    assert_eq!(table.rows[0], vec!["Alice", "30", "New York", "31"]);
+
+    table.drop_column("Next").unwrap();
+    table.drop_column("Name").unwrap();
+    assert_eq!(table.rows[0], vec!["30", "New York"]);
+}
+
+#[test]
+fn test_rename_and_drop_column() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.rename_column("Age", "Years").unwrap();
+    table.rename_column(0, "Person").unwrap();
+    assert_eq!(table.columns[0].header, "Person");
+    assert_eq!(table.columns[1].header, "Years");
+
+    table.drop_column("Years").unwrap();
+    assert_eq!(table.columns.len(), 2);
+    assert_eq!(table.rows[0], vec!["Alice", "New York"]);
+
+    assert_eq!(
+        table.drop_column("Years"),
+        Err(TableError::ColumnNotFound("Years".to_string()))
+    );
+    assert_eq!(
+        table.rename_column(2, "x"),
+        Err(TableError::ColumnOutOfRange(2))
+    );
+}
+
+#[test]
+fn test_drop_column_keeps_grand_total_and_locked_widths_aligned() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.set_footer("Age", Aggregate::Sum).unwrap();
+    let mut last = table
+        .pages(&PaginationOptions {
+            rows_per_page: 1,
+            page_subtotals: false,
+        })
+        .pop()
+        .unwrap();
+    last.drop_column("Name").unwrap();
+    assert_eq!(last.columns.len(), 2);
+    assert_eq!(last.locked_widths, Some(vec![5, 13]));
+    assert_eq!(
+        last.render_lines(),
+        [
+            "+-------+---------------+",
+            "|   Age |     City      |",
+            "+-------+---------------+",
+            "|    25 |  Los Angeles  |",
+            "+-------+---------------+",
+            "|    55 |               |",
+            "+-------+---------------+",
+        ]
+    );
+}
+
+fn validated_table() -> Table {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Name", 8, Alignment::Left);
+    table.add_column("Age", 4, Alignment::Right);
+    table.add_column("Tier", 6, Alignment::Left);
+    table.column_mut(1).unwrap().validator(Validator::Range {
+        min: 0.0,
+        max: 130.0,
+    });
+    table
+        .column_mut(2)
+        .unwrap()
+        .validator(Validator::OneOf(vec![
+            "free".to_string(),
+            "pro".to_string(),
+        ]));
+    table
+}
+
+#[test]
+fn test_validation_flags_offending_cells() {
+    let mut table = validated_table();
+    table.add_row(vec!["Ann".to_string(), "34".to_string(), "pro".to_string()]);
+    table.add_row(vec!["Bo".to_string(), "-3".to_string(), "gold".to_string()]);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.violations().len(), 2);
+    assert!(matches!(
+        table.violations()[1],
+        TableError::InvalidCell {
+            row: 1,
+            column: 2,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_strict_validation_rejects_rows() {
+    let mut table = validated_table();
+    table.set_strict(true);
+    let result = table.try_add_row(vec![
+        "Cy".to_string(),
+        "abc".to_string(),
+        "free".to_string(),
+    ]);
+    assert_eq!(
+        result,
+        Err(TableError::InvalidCell {
+            row: 0,
+            column: 1,
+            value: "abc".to_string(),
+            reason: "expected a number between 0 and 130".to_string(),
+        })
+    );
+    assert!(table.rows.is_empty());
+    assert_eq!(
+        table.try_add_row(vec!["Cy".to_string()]),
+        Err(TableError::RowLength {
+            row: 0,
+            expected: 3,
+            found: 1
+        })
+    );
+}
+
+#[test]
+fn test_strict_add_row_records_violations() {
+    let mut table = validated_table();
+    table.set_strict(true);
+    table.add_row(vec!["Di".to_string(), "40".to_string(), "gold".to_string()]);
+    assert_eq!(table.rows.len(), 1);
+    assert_eq!(
+        table.violations(),
+        [TableError::InvalidCell {
+            row: 0,
+            column: 2,
+            value: "gold".to_string(),
+            reason: "expected one of free, pro".to_string(),
+        }]
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_pattern_validator() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Id", 8, Alignment::Left);
+    table.column_mut(0).unwrap().validator(Validator::Pattern(
+        regex::Regex::new(r"^[A-Z]{3}-\d+$").unwrap(),
+    ));
+    table.set_strict(true);
+    assert!(table.try_add_row(vec!["ABC-12".to_string()]).is_ok());
+    assert!(table.try_add_row(vec!["abc-12".to_string()]).is_err());
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_csv_import_options() {
+    let data = "id,name,city,score\n1, Ann ,Oslo,3\n2,Bo,Rome,5\n3,Cy,Lima,7\n4,Di,Kiev,9\n";
+    let options = CsvImportOptions {
+        skip_rows: 1,
+        max_rows: Some(2),
+        columns: Selection::Names(vec!["score".to_string(), "name".to_string()]),
+        trim: true,
+        ..CsvImportOptions::default()
+    };
+    let table = Table::from_csv_reader(data.as_bytes(), &options).unwrap();
+    assert_eq!(table.columns.len(), 2);
+    assert_eq!(table.columns[0].header, "score");
+    assert_eq!(table.rows, vec![vec!["5", "Bo"], vec!["7", "Cy"]]);
+
+    let untrimmed = Table::from_csv_reader(data.as_bytes(), &CsvImportOptions::default()).unwrap();
+    assert_eq!(untrimmed.rows[0][1], " Ann ");
+
+    let missing = CsvImportOptions {
+        columns: Selection::Names(vec!["zip".to_string()]),
+        ..CsvImportOptions::default()
+    };
+    assert!(Table::from_csv_reader(data.as_bytes(), &missing).is_err());
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_sniff_csv() {
+    let semicolons = "name;age;city\nAnn;34;\"Oslo; Norway\"\nBo;27;Rome\n";
+    assert_eq!(
+        sniff_csv(semicolons.as_bytes()),
+        CsvDialect {
+            delimiter: b';',
+            has_headers: true
+        }
+    );
+    let headerless_tabs = "Ann\t34\t1.5\nBo\t27\t2.5\nCy\t41\t3.0\n";
+    assert_eq!(
+        sniff_csv(headerless_tabs.as_bytes()),
+        CsvDialect {
+            delimiter: b'\t',
+            has_headers: false
+        }
+    );
+    assert!(!sniff_csv(b"Ann;34\nBo;27").has_headers);
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_from_csv_reader_sniffed() {
+    let data = "Ann|34\nBo|27\n";
+    let (table, dialect) =
+        Table::from_csv_reader_sniffed(data.as_bytes(), &CsvImportOptions::default()).unwrap();
+    assert_eq!(dialect.delimiter, b'|');
+    assert!(!dialect.has_headers);
+    assert_eq!(table.columns[0].header, "Column 1");
+    assert_eq!(table.rows, vec![vec!["Ann", "34"], vec!["Bo", "27"]]);
+
+    let (_, dialect) =
+        Table::from_csv_reader_sniffed(&b"Ann|34\nBo|27"[..], &CsvImportOptions::default())
+            .unwrap();
+    assert!(!dialect.has_headers);
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn test_csv_import_transcodes_latin1() {
+    let data: &[u8] = b"name,city\nJos\xe9,M\xfcnchen\n";
+    let options = CsvImportOptions {
+        encoding: Some("latin-1".to_string()),
+        ..CsvImportOptions::default()
+    };
+    let table = Table::from_csv_reader(data, &options).unwrap();
+    assert_eq!(table.rows, vec![vec!["José", "München"]]);
+
+    let unknown = CsvImportOptions {
+        encoding: Some("klingon".to_string()),
+        ..CsvImportOptions::default()
+    };
+    assert!(Table::from_csv_reader(data, &unknown).is_err());
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_from_compressed_reader_gzip() {
+    use std::io::Write as _;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(b"host,status\nweb1,up\n").unwrap();
+    let compressed = encoder.finish().unwrap();
+    let table =
+        Table::from_compressed_reader(compressed.as_slice(), &CsvImportOptions::default()).unwrap();
+    assert_eq!(table.rows, vec![vec!["web1", "up"]]);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_from_compressed_reader_zstd() {
+    let compressed = zstd::encode_all(&b"host,status\nweb2,down\n"[..], 3).unwrap();
+    let table =
+        Table::from_compressed_reader(compressed.as_slice(), &CsvImportOptions::default()).unwrap();
+    assert_eq!(table.rows, vec![vec!["web2", "down"]]);
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_from_compressed_reader_passes_plain_text_through() {
+    let table =
+        Table::from_compressed_reader(&b"a,b\n1,2\n"[..], &CsvImportOptions::default()).unwrap();
+    assert_eq!(table.rows, vec![vec!["1", "2"]]);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_from_json_reader() {
+    let objects = r#"[{"name": "Ann", "age": 34}, {"name": "Bo", "city": null, "admin": true}]"#;
+    let table = Table::from_json_reader(objects.as_bytes()).unwrap();
+    let headers: Vec<&str> = table.columns.iter().map(|c| c.header.as_str()).collect();
+    assert_eq!(headers, vec!["name", "age", "city", "admin"]);
+    assert_eq!(
+        table.rows,
+        vec![vec!["Ann", "34", "", ""], vec!["Bo", "", "", "true"]]
+    );
+
+    let arrays = r#"[["name", "age"], ["Cy", 41]]"#;
+    let table = Table::from_json_reader(arrays.as_bytes()).unwrap();
+    assert_eq!(table.rows, vec![vec!["Cy", "41"]]);
+
+    assert!(Table::from_json_reader(&b"{\"a\": 1}"[..]).is_err());
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn test_from_url_serves_csv() {
+    use std::io::{Read as _, Write as _};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/data.csv", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 1024];
+        let _ = stream.read(&mut request).unwrap();
+        let body = "host,load\nweb1,0.5\n";
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+    });
+    let table = Table::from_url(&url, DataFormat::Csv).unwrap();
+    assert_eq!(table.rows, vec![vec!["web1", "0.5"]]);
+}
+
+#[test]
+fn test_to_markdown_and_tsv() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.add_row(vec![
+        "Pipe|Tab".to_string(),
+        "1".to_string(),
+        "A\tB".to_string(),
+    ]);
+    assert_eq!(
+        table.to_markdown(),
+        "| Name | Age | City |\n\
+         | :--- | ---: | :---: |\n\
+         | Alice | 30 | New York |\n\
+         | Bob | 25 | Los Angeles |\n\
+         | Pipe\\|Tab | 1 | A\tB |\n"
+    );
+    assert_eq!(
+        table.to_tsv(),
+        "Name\tAge\tCity\nAlice\t30\tNew York\nBob\t25\tLos Angeles\nPipe|Tab\t1\tA B\n"
+    );
+}
+
+#[cfg(feature = "pager")]
+#[test]
+fn test_pager_command() {
+    use crate::pager::pager_command;
+
+    assert_eq!(pager_command(None), Some(("less", vec!["-R"])));
+    assert_eq!(
+        pager_command(Some("most -s -w")),
+        Some(("most", vec!["-s", "-w"]))
+    );
+    assert_eq!(pager_command(Some("  ")), None);
+}
+
+#[test]
+fn test_table_watcher_debounces_updates() {
+    let (mut watcher, updates) = TableWatcher::new(numbered_table(2));
+    updates
+        .send(RowUpdate::Append(vec!["2".to_string()]))
+        .unwrap();
+    updates.send(RowUpdate::Clear).unwrap();
+    updates
+        .send(RowUpdate::Replace(vec![
+            vec!["7".to_string()],
+            vec!["8".to_string()],
+        ]))
+        .unwrap();
+    drop(updates);
+
+    let mut renders = Vec::new();
+    watcher
+        .run_with(|table| {
+            renders.push(table.rows.clone());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(renders, vec![vec![vec!["7"], vec!["8"]]]);
+}
+
+#[test]
+fn test_table_watcher_skips_rejected_updates() {
+    let mut table = numbered_table(2);
+    table.set_strict(true);
+    table
+        .column_mut(0)
+        .unwrap()
+        .validator(Validator::Range { min: 0.0, max: 9.0 });
+    let (mut watcher, updates) = TableWatcher::new(table);
+    let rejected = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = rejected.clone();
+    watcher.on_rejected(move |error| log.lock().unwrap().push(error.clone()));
+    for update in [
+        RowUpdate::Append(vec!["2".to_string(), "extra".to_string()]),
+        RowUpdate::Replace(vec![vec!["7".to_string()], vec!["42".to_string()]]),
+        RowUpdate::Append(vec!["3".to_string()]),
+    ] {
+        updates.send(update).unwrap();
+    }
+    drop(updates);
+
+    watcher.run_with(|_| Ok(())).unwrap();
+    let rows: Vec<&str> = watcher.table().rows.iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(rows, vec!["0", "1", "3"]);
+    let rejected = rejected.lock().unwrap();
+    assert_eq!(rejected.len(), 2);
+    assert!(matches!(
+        rejected[0],
+        TableError::RowLength { found: 2, .. }
+    ));
+    assert!(matches!(
+        rejected[1],
+        TableError::InvalidCell {
+            row: 1,
+            column: 0,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_concurrent_table_builder() {
+    let builder = ConcurrentTableBuilder::new(numbered_table(0));
+    let workers: Vec<_> = (0..4)
+        .map(|worker| {
+            let builder = builder.clone();
+            std::thread::spawn(move || {
+                for i in 0..25 {
+                    builder
+                        .push_row(vec![(worker * 25 + i).to_string()])
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    let snapshot = builder.snapshot();
+    assert!(snapshot.rows.len() <= 100);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    assert_eq!(
+        builder.push_row(vec![]),
+        Err(TableError::RowLength {
+            row: 100,
+            expected: 1,
+            found: 0
+        })
+    );
+
+    let mut table = builder.into_table();
+    assert_eq!(table.rows.len(), 100);
+    table
+        .sort_by_cached_key(0, |n| n.parse::<usize>().unwrap())
+        .unwrap();
+    assert_eq!(table.rows[99], vec!["99"]);
+}
+
+#[test]
+fn test_table_moves_across_threads() {
+    let table = std::thread::spawn(|| {
+        let mut table = create_test_table(TableStyle::Grid);
+        table.add_computed_column("Initial", |cells| cells[0][..1].to_string());
+        table
+    })
+    .join()
+    .unwrap();
+
+    let shared = std::sync::Arc::new(table);
+    let rendered: Vec<Vec<u8>> = (0..2)
+        .map(|_| {
+            let table = std::sync::Arc::clone(&shared);
+            std::thread::spawn(move || {
+                let mut output = Vec::new();
+                table.print_to_writer(&mut output).unwrap();
+                output
+            })
+        })
+        .map(|worker| worker.join().unwrap())
+        .collect();
+    assert_eq!(rendered[0], rendered[1]);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_print_color_flushes_buffered_writers() {
+    let table = create_test_table(TableStyle::Grid);
+    let mut writer = termcolor::Ansi::new(std::io::BufWriter::new(Vec::new()));
+    table.print_color(&mut writer).unwrap();
+    let buffered = writer.into_inner();
+    assert!(buffered.buffer().is_empty());
+
+    let mut plain = Vec::new();
+    table.print_to_writer(&mut plain).unwrap();
+    assert_eq!(buffered.get_ref(), &plain);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_print_paths_share_one_layout() {
+    let mut table = Table::new(TableStyle::Minimal);
+    table.add_column("Name", 3, Alignment::Left);
+    table.add_row(vec!["Maximilian".to_string()]);
+    let mut plain = Vec::new();
+    table.print_to_writer(&mut plain).unwrap();
+    let plain = String::from_utf8(plain).unwrap();
+    assert!(plain.starts_with("┌────────────┐\n│ Name       │\n"));
+    assert_eq!(table.compute_layout().column_widths, vec![10]);
+
+    let mut colored = termcolor::Buffer::ansi();
+    table.print_color(&mut colored).unwrap();
+    assert_eq!(String::from_utf8(colored.into_inner()).unwrap(), plain);
+
+    let amiga = create_test_table(TableStyle::Amiga);
+    let mut ansi = Vec::new();
+    amiga
+        .print_ansi(&mut ansi, &amiga.render_options())
+        .unwrap();
+    assert!(String::from_utf8(ansi).unwrap().ends_with("\x1b[0m"));
+    let mut colorless = termcolor::Buffer::no_color();
+    amiga.print_color(&mut colorless).unwrap();
+    assert!(!String::from_utf8(colorless.into_inner())
+        .unwrap()
+        .contains('\x1b'));
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_render_options_leave_table_untouched() {
+    let table = create_test_table(TableStyle::Amiga);
+    let render = |options: &RenderOptions| {
+        let mut buffer = termcolor::Buffer::ansi();
+        table.print_with(&mut buffer, options).unwrap();
+        String::from_utf8(buffer.into_inner()).unwrap()
+    };
+
+    let mut options = table.render_options();
+    options.style = TableStyle::FancyGrid;
+    options.charset = Charset::Ascii;
+    options.show_header = false;
+    options.max_width = Some(24);
+    assert_eq!(
+        render(&options),
+        "+------+-------+-------+\n\
+         | A... |    30 | Ne... |\n\
+         | Bob  |    25 | Lo... |\n\
+         +------+-------+-------+\n"
+    );
+
+    let mut clipped = create_test_table(TableStyle::Amiga);
+    clipped.set_max_rows(1);
+    let mut buffer = termcolor::Buffer::ansi();
+    clipped.print_with(&mut buffer, &options).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(output.is_ascii());
+    assert!(output.ends_with("... and 1 more row\n"));
+
+    let mut options = table.render_options();
+    options.color = false;
+    assert!(!render(&options).contains('\x1b'));
+    assert!(render(&table.render_options()).contains('\x1b'));
+}
+
+#[test]
+fn test_side_by_side() {
+    let before = numbered_table(3);
+    let mut after = numbered_table(1);
+    after.style = TableStyle::Grid;
+    assert_eq!(
+        layout::side_by_side(&[&before, &after], 2),
+        "  N  +------+\n\
+         \x20 0  |    N |\n\
+         \x20 1  +------+\n\
+         \x20 2  |    0 |\n\
+         \x20    +------+\n"
+    );
+}
+
+#[test]
+fn test_grid_wraps_panels_to_width() {
+    let small = numbered_table(1);
+    let mut grid = Grid::new();
+    grid.add_panel("One", &small)
+        .add(&small)
+        .add_panel("Three", &small)
+        .width(12);
+    assert_eq!(
+        grid.render(),
+        "One\n  N    N\n  0    0\n\nThree\n  N\n  0\n"
+    );
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_legend_renders_swatches() {
+    let mut table = numbered_table(1);
+    let mut ok = ColorSpec::new();
+    ok.set_fg(Some(Color::Green));
+    let mut degraded = ColorSpec::new();
+    degraded.set_fg(Some(Color::Yellow));
+    table.set_legend(&[(ok, "ok"), (degraded, "degraded")]);
+
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "  N\n  0\n■ ok  ■ degraded\n"
+    );
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table.print_color(&mut buffer).unwrap();
+    let colored = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(colored.ends_with("\x1b[0m\x1b[33m■\x1b[0m degraded\n"));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn test_interactive_session_sorts_and_filters() {
+    use crate::interact::{Control, Session};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let table = sales_table();
+    let mut session = Session::new(&table);
+    let mut press = |code| session.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    press(KeyCode::Tab);
+    press(KeyCode::Tab);
+    press(KeyCode::Char('r'));
+    press(KeyCode::Char('/'));
+    press(KeyCode::Char('0'));
+    press(KeyCode::Enter);
+    assert_eq!(press(KeyCode::Char('q')), Control::Quit);
+
+    assert_eq!(
+        session.view_table().rows,
+        vec![vec!["a", "1,200"], vec!["c", "300.5"]]
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn test_interactive_row_selection() {
+    use crate::interact::{Control, Session};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let table = sales_table();
+    let mut session = Session::picking(&table);
+    let mut press = |code| session.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    press(KeyCode::Down);
+    press(KeyCode::Down);
+    press(KeyCode::Down);
+    press(KeyCode::Down);
+    press(KeyCode::Up);
+    assert_eq!(press(KeyCode::Enter), Control::Select);
+    assert_eq!(
+        session.selected(),
+        Some((2, vec!["c".to_string(), "300.5".to_string()]))
+    );
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn test_interactive_resize_keeps_cursor_in_view() {
+    use crate::interact::Session;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let table = numbered_table(20);
+    let mut session = Session::picking(&table);
+    session.resize(40);
+    session.handle(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+    assert_eq!(session.offset, 0);
+    session.resize(6);
+    assert_eq!(session.offset, 16);
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn test_interactive_filter_reports_matches() {
+    use crate::interact::Session;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let table = sales_table();
+    let mut session = Session::new(&table);
+    for code in [KeyCode::Char('/'), KeyCode::Char('3'), KeyCode::Char('0')] {
+        session.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+    assert_eq!(session.view_table().rows, vec![vec!["c", "300.5"]]);
+    assert_eq!(session.hits[2], vec![None, Some(vec![0, 1])]);
+    assert_eq!(session.match_summary(), "Amount 1");
+}
+
+#[test]
+fn test_render_viewport() {
+    let mut table = numbered_table(100);
+    table.style = TableStyle::Grid;
+    assert_eq!(
+        table.render_viewport(42, 6),
+        vec!["+------+", "|    N |", "+------+", "|   42 |", "|   43 |", "+------+",]
+    );
+    assert_eq!(table.render_viewport(99, 10).len(), 5);
+    assert_eq!(table.render_viewport(500, 10).len(), 4);
+}
+
+#[test]
+fn test_render_viewport_goes_through_the_full_renderer() {
+    let mut table = numbered_table(12);
+    table.show_index(IndexFormat::Number);
+    table.set_right_to_left(true);
+    let lines = table.render_lines();
+    assert_eq!(lines[9], "8   9");
+    assert_eq!(
+        table.render_viewport(8, 3),
+        [&lines[0], &lines[9], &lines[10]].map(String::as_str)
+    );
+
+    let mut tall = Table::new(TableStyle::Grid);
+    tall.add_column("Text", 3, Alignment::Left);
+    for text in ["a\nb\nc\nd", "e", "f\ng"] {
+        tall.add_row(vec![text.to_string()]);
+    }
+    tall.set_max_row_height(2);
+    assert_eq!(
+        tall.render_viewport(0, 7),
+        [
+            "+------+",
+            "| Text |",
+            "+------+",
+            "| a    |",
+            "| b…   |",
+            "| e    |",
+            "+------+"
+        ]
+    );
+    assert_eq!(
+        tall.render_viewport(1, 7)[3..6],
+        ["| e    |", "| f    |", "| g    |"]
+    );
+}
+
+#[test]
+fn test_column_window_keeps_pinned_columns() {
+    let mut table = Table::new(TableStyle::Grid);
+    for header in ["Id", "Alpha", "Beta", "Gamma"] {
+        table.add_column(header, 5, Alignment::Left);
+    }
+    table.add_row(vec![
+        "7".to_string(),
+        "a".to_string(),
+        "b".to_string(),
+        "c".to_string(),
+    ]);
+    table.column_mut(0).unwrap().pinned(true);
+
+    let headers = |window: &Table| -> Vec<String> {
+        window.columns.iter().map(|c| c.header.clone()).collect()
+    };
+    assert_eq!(
+        headers(&table.column_window(0, 25)),
+        ["Id", "Alpha", "Beta"]
+    );
+    assert_eq!(headers(&table.column_window(2, 24)), ["Id", "Gamma"]);
+    assert_eq!(headers(&table.column_window(1, 5)), ["Id", "Beta"]);
+    assert_eq!(table.column_window(2, 24).rows, vec![vec!["7", "c"]]);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_column_paging_splits_wide_tables() {
+    let mut table = Table::new(TableStyle::Grid);
+    for header in ["Id", "Alpha", "Beta", "Gamma"] {
+        table.add_column(header, 5, Alignment::Left);
+    }
+    table.add_row(vec![
+        "7".to_string(),
+        "a".to_string(),
+        "b".to_string(),
+        "c".to_string(),
+    ]);
+    table.column_mut(0).unwrap().pinned(true);
+    table.set_column_paging(true);
+
+    assert_eq!(table.column_pages(25).len(), 2);
+    let mut options = table.render_options();
+    options.max_width = Some(25);
+    let mut buffer = termcolor::NoColor::new(Vec::new());
+    table.print_with(&mut buffer, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(buffer.into_inner()).unwrap(),
+        "+-------+-------+-------+\n\
+         | Id    | Alpha | Beta  |\n\
+         +-------+-------+-------+\n\
+         | 7     | a     | b     |\n\
+         +-------+-------+-------+\n\
+         \n\
+         +-------+-------+\n\
+         | Id    | Gamma |\n\
+         +-------+-------+\n\
+         | 7     | c     |\n\
+         +-------+-------+\n"
+    );
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_low_priority_columns_hide_first() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.add_column("Notes", 12, Alignment::Left);
+    table.rows[0].push("likes tea".to_string());
+    table.rows[1].push(String::new());
+    table.column_mut(3).unwrap().priority(1);
+    table.column_mut(2).unwrap().priority(2);
+
+    let render = |width| {
+        let mut options = table.render_options();
+        options.max_width = Some(width);
+        let mut buffer = termcolor::NoColor::new(Vec::new());
+        table.print_with(&mut buffer, &options).unwrap();
+        String::from_utf8(buffer.into_inner()).unwrap()
+    };
+    assert!(!render(80).contains("Hidden"));
+    assert!(render(40).ends_with("+\nHidden columns: Notes\n"));
+    assert!(render(20).ends_with("+\nHidden columns: City, Notes\n"));
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_breakpoints_pick_variant_by_width() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.add_breakpoint(..30, LayoutVariant::Records).unwrap();
+    table
+        .add_breakpoint(30..40, LayoutVariant::Hide(vec!["City".into()]))
+        .unwrap();
+    assert_eq!(
+        table.add_breakpoint(40.., LayoutVariant::Hide(vec!["Notes".into()])),
+        Err(TableError::ColumnNotFound("Notes".to_string()))
+    );
+
+    let render = |width| {
+        let mut options = table.render_options();
+        options.max_width = Some(width);
+        let mut buffer = termcolor::NoColor::new(Vec::new());
+        table.print_with(&mut buffer, &options).unwrap();
+        String::from_utf8(buffer.into_inner()).unwrap()
+    };
+    assert_eq!(
+        render(20),
+        "Name: Alice\nAge : 30\nCity: New York\n\nName: Bob\nAge : 25\nCity: Los Angeles\n"
+    );
+    assert!(render(35).starts_with("+----------+-------+\n| Name     |   Age |\n"));
+    assert!(render(80).contains("|  Los Angeles  |"));
+
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), render(DEFAULT_WIDTH));
+    let caps = TermCaps::from_env(true, Some((25, 50)), |_| None);
+    assert_eq!(
+        table.render_options().for_terminal(&caps).max_width,
+        Some(25)
+    );
+}
+
+#[test]
+fn test_truncation_marker_and_position() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Path", 10, Alignment::Left);
+    table.add_column("Id", 8, Alignment::Left);
+    table.add_row(vec![
+        "/usr/local/share/doc/readme.md".to_string(),
+        "a1b2c3d4e5f6".to_string(),
+    ]);
+    table.set_total_width(25, Distribution::Even);
+    table
+        .column_mut(0)
+        .unwrap()
+        .truncate(Truncate::Start)
+        .truncation_marker("..");
+    table
+        .column_mut(1)
+        .unwrap()
+        .truncate(Truncate::Middle)
+        .truncation_marker("[+]");
+
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().nth(3), Some("| ..adme.md | a1b[+]5f6 |"));
+}
+
+#[test]
+fn test_header_alignment_is_independent() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table
+        .column_mut(1)
+        .unwrap()
+        .header_alignment(Alignment::Center);
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[1], "| Name     |  Age  |     City      |");
+    assert_eq!(lines[3], "| Alice    |    30 |   New York    |");
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_short_rows_are_padded_with_default_cell() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.set_default_cell("n/a");
+    table.set_row_policy(RowPolicy::Pad);
+    table.add_row(vec!["Dan".to_string()]);
+    assert_eq!(table.rows[2], vec!["Dan", "n/a", "n/a"]);
+    let long = vec!["a", "b", "c", "d"].into_iter().map(String::from);
+    assert!(table.try_add_row(long.clone().collect()).is_err());
+
+    table.set_row_policy(RowPolicy::PadOrTruncate);
+    table.try_add_row(long.collect()).unwrap();
+    assert_eq!(table.rows[3], vec!["a", "b", "c"]);
+
+    let data = "id,name,city\n1,Ann\n2,Bo,Rome\n";
+    let options = CsvImportOptions {
+        row_policy: RowPolicy::Pad,
+        ..CsvImportOptions::default()
+    };
+    let imported = Table::from_csv_reader(data.as_bytes(), &options).unwrap();
+    assert_eq!(imported.rows[0], vec!["1", "Ann", ""]);
+    assert!(Table::from_csv_reader(data.as_bytes(), &CsvImportOptions::default()).is_err());
+}
+
+#[test]
+fn test_try_add_rows_collects_errors() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.set_strict(true);
+    table.column_mut(1).unwrap().validator(Validator::Range {
+        min: 0.0,
+        max: 150.0,
+    });
+    let rows = vec![
+        vec!["Carol", "41", "Paris"],
+        vec!["Dan"],
+        vec!["Eve", "old", "Rome"],
+        vec!["Finn", "19", "Oslo"],
+    ];
+    let errors = table
+        .try_add_rows(
+            rows.into_iter()
+                .map(|row| row.into_iter().map(String::from).collect()),
+        )
+        .unwrap_err();
+    assert_eq!(table.rows.len(), 4);
+    let indices: Vec<usize> = errors.errors().iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, vec![1, 2]);
+    assert!(matches!(
+        errors.errors()[0].1,
+        TableError::RowLength { found: 1, .. }
+    ));
+    assert!(errors.to_string().starts_with("2 rows rejected"));
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_csv_errors_report_position() {
+    let data = "id,name\n1,Ann\n2,Bo,Rome\n";
+    let Err(error) = Table::from_csv_reader(data.as_bytes(), &CsvImportOptions::default()) else {
+        panic!("expected an error");
+    };
+    let inner = error.into_inner().unwrap();
+    let error = inner.downcast_ref::<TableError>().unwrap();
+    assert_eq!(
+        error,
+        &TableError::Csv {
+            record: 3,
+            line: 3,
+            byte: 14,
+            field: None,
+            message: "expected 2 fields, found 3".to_string(),
+        }
+    );
+
+    let data = b"id,name\n1,A\xffn\n";
+    let Err(error) = Table::from_csv_reader(&data[..], &CsvImportOptions::default()) else {
+        panic!("expected an error");
+    };
+    assert!(error
+        .to_string()
+        .starts_with("CSV record 2 (line 2, byte 8), field 1:"));
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_from_csv_returns_table_errors() {
+    let missing = Table::from_csv("examples/missing.csv").err().unwrap();
+    assert!(matches!(
+        missing,
+        TableError::Io {
+            kind: std::io::ErrorKind::NotFound,
+            ..
+        }
+    ));
+
+    let path = std::env::temp_dir().join(format!("tabprinter-{}.csv", std::process::id()));
+    std::fs::write(&path, "id,name\n1,Ann\n2,Bo,Rome\n").unwrap();
+    let malformed = Table::from_csv(path.to_str().unwrap()).err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        malformed,
+        Some(TableError::Csv {
+            record: 3,
+            line: 3,
+            byte: 14,
+            field: None,
+            message: "expected 2 fields, found 3".to_string(),
+        })
+    );
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_lossy_utf8_import() {
+    let data = b"id,n\xe4me,x\xff\n1,A\xffn,\xfe\n2,Bo,3\n";
+    let options = CsvImportOptions {
+        lossy_utf8: true,
+        columns: Selection::Names(vec!["x\u{fffd}".to_string(), "id".to_string()]),
+        ..CsvImportOptions::default()
+    };
+    let table = Table::from_csv_reader(&data[..], &options).unwrap();
+    assert_eq!(table.columns[0].header, "x\u{fffd}");
+    assert_eq!(table.rows[0], vec!["\u{fffd}", "1"]);
+    assert!(table.violations().is_empty());
+    let report = table.import_report();
+    assert_eq!(report.repaired_headers(), &[0]);
+    assert_eq!(report.repaired_cells(), &[(0, 0)]);
+    assert_eq!(report.repaired(), 2);
+
+    let options = CsvImportOptions {
+        lossy_utf8: true,
+        ..CsvImportOptions::default()
+    };
+    let table = Table::from_csv_reader(&data[..], &options).unwrap();
+    assert_eq!(table.rows[0][1], "A\u{fffd}n");
+    assert_eq!(table.rows[1][1], "Bo");
+    assert_eq!(table.import_report().repaired_headers(), &[1, 2]);
+    assert_eq!(table.import_report().repaired_cells(), &[(0, 1), (0, 2)]);
+}
+
+#[test]
+fn test_filter_expr() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec![
+        "Carol".to_string(),
+        "41".to_string(),
+        "NYC".to_string(),
+    ]);
+    let names = |t: &Table| t.rows.iter().map(|r| r[0].clone()).collect::<Vec<_>>();
+
+    let filtered = table.filter_expr("Age > 26 && City != 'NYC'").unwrap();
+    assert_eq!(names(&filtered), vec!["Alice"]);
+    let filtered = table
+        .filter_expr("!(City contains 'York') || Name == \"Alice\"")
+        .unwrap();
+    assert_eq!(names(&filtered), vec!["Alice", "Bob", "Carol"]);
+    assert_eq!(
+        names(&table.filter_expr("`Age` <= 30 && Age >= 30").unwrap()),
+        vec!["Alice"]
+    );
+
+    assert_eq!(
+        table.filter_expr("Height > 3").err(),
+        Some(TableError::ColumnNotFound("Height".to_string()))
+    );
+    assert!(matches!(
+        table.filter_expr("Age > "),
+        Err(TableError::InvalidExpression { position: 6, .. })
+    ));
+}
+
+#[test]
+fn test_filter_expr_number_against_non_numeric_cells() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec![
+        "Dee".to_string(),
+        "n/a".to_string(),
+        "Oslo".to_string(),
+    ]);
+    table.add_row(vec!["Eve".to_string(), "".to_string(), "Rome".to_string()]);
+    let names = |expr| {
+        table
+            .filter_expr(expr)
+            .unwrap()
+            .rows
+            .iter()
+            .map(|r| r[0].clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("Age > 26"), vec!["Alice"]);
+    assert_eq!(names("Age >= 0"), vec!["Alice", "Bob"]);
+    assert_eq!(names("Age < 30"), vec!["Bob"]);
+    assert_eq!(names("Age <= 99"), vec!["Alice", "Bob"]);
+    assert_eq!(names("Age == 30"), vec!["Alice"]);
+    assert_eq!(names("Age != 30"), vec!["Bob", "Dee", "Eve"]);
+    assert_eq!(names("Age == 'n/a'"), vec!["Dee"]);
+}
+
+#[test]
+fn test_filter_expr_limits_nesting() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec!["NaN".to_string(), "inf".to_string(), "x".to_string()]);
+    let names = |t: &Table| t.rows.iter().map(|r| r[0].clone()).collect::<Vec<_>>();
+    assert_eq!(
+        names(&table.filter_expr("Name == 'NaN'").unwrap()),
+        vec!["NaN"]
+    );
+    assert_eq!(
+        names(&table.filter_expr("Age == 'inf'").unwrap()),
+        vec!["NaN"]
+    );
+
+    let nested = |depth: usize| format!("{}Age > 1{}", "(".repeat(depth), ")".repeat(depth));
+    assert!(table.filter_expr(&nested(60)).is_ok());
+    let chain = vec!["City != 'x'"; 5000].join(" && ");
+    assert_eq!(table.filter_expr(&chain).unwrap().rows.len(), 2);
+    for expr in [
+        "!".repeat(5000) + "true",
+        nested(5000),
+        "-".repeat(5000) + "1 > 0",
+        vec!["Age"; 5000].join(" + ") + " > 0",
+    ] {
+        assert!(matches!(
+            table.filter_expr(&expr),
+            Err(TableError::InvalidExpression { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_computed_column_expr() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("item", 6, Alignment::Left);
+    table.add_column("price", 6, Alignment::Right);
+    table.add_column("qty", 4, Alignment::Right);
+    table.add_row(vec!["pen".to_string(), "1.5".to_string(), "3".to_string()]);
+    table
+        .add_computed_column_expr("total", "price * qty - (1 + 1) / 4")
+        .unwrap();
+    table
+        .add_computed_column_expr("label", "item + ':' + qty")
+        .unwrap();
+    table.add_row(vec![
+        "ink".to_string(),
+        "2,000".to_string(),
+        "-2".to_string(),
+    ]);
+    assert_eq!(table.rows[0][3..], ["4", "pen:3"]);
+    assert_eq!(table.rows[1][3..], ["-4000.5", "ink:-2"]);
+    assert!(table.add_computed_column_expr("bad", "price *").is_err());
+    assert_eq!(table.filter_expr("total > 0").unwrap().rows.len(), 1);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_highlight_and_filter() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Ref", 10, Alignment::Left);
+    table.add_row(vec!["ab-123".to_string()]);
+    table.add_row(vec!["cd".to_string()]);
+    let mut red = ColorSpec::new();
+    red.set_fg(Some(Color::Red));
+    table
+        .column_mut(0)
+        .unwrap()
+        .highlight(regex::Regex::new(r"-(\d+)").unwrap(), red);
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table.print_color(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(output.contains("ab-\x1b[0m\x1b[31m123\x1b[0m\n"));
+
+    let pattern = regex::Regex::new(r"^\w+-").unwrap();
+    assert_eq!(table.filter_regex("Ref", &pattern).unwrap().rows.len(), 1);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_signed_colors() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Delta", 6, Alignment::Right);
+    for value in ["-1.5", "+3%", "0", "n/a"] {
+        table.add_row(vec![value.to_string()]);
+    }
+    table.column_mut(0).unwrap().signed_colors(true);
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table.print_color(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[1], " \x1b[0m\x1b[31m-1.5\x1b[0m");
+    assert_eq!(lines[2], "  \x1b[0m\x1b[32m+3%\x1b[0m");
+    assert_eq!(lines[3], "    \x1b[0m\x1b[2m0\x1b[0m");
+    assert_eq!(lines[4], "  n/a");
+}
+
+#[test]
+fn test_percent_bar_format() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Done", 14, Alignment::Left);
+    for value in ["47", "100%", "0.4", "n/a"] {
+        table.add_row(vec![value.to_string()]);
+    }
+    table
+        .column_mut(0)
+        .unwrap()
+        .format(Format::Percent { bar: true });
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[1], " 47% ████▊");
+    assert_eq!(lines[2], "100% ██████████");
+    assert_eq!(lines[3], "  0%");
+    assert_eq!(lines[4], "n/a");
+    assert_eq!(table.rows[0][0], "47");
+}
+
+#[test]
+fn test_humanized_formats_sort_by_magnitude() {
+    assert_eq!(humanize_bytes(512), "512 B");
+    assert_eq!(humanize_bytes(1536), "1.5 KiB");
+    assert_eq!(humanize_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    assert_eq!(humanize_count(999), "999");
+    assert_eq!(humanize_count(1_234_567), "1.2M");
+    assert_eq!(humanize_count(-45_000), "-45.0K");
+
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Size", 4, Alignment::Right);
+    for size in ["2 MiB", "1536", "900 KiB", "?", "12"] {
+        table.add_row(vec![size.to_string()]);
+    }
+    table.column_mut(0).unwrap().format(Format::Bytes);
+    table.sort_by_value("Size").unwrap();
+    let sizes: Vec<&str> = table.rows.iter().map(|r| r[0].as_str()).collect();
+    assert_eq!(sizes, vec!["12", "1536", "900 KiB", "2 MiB", "?"]);
+
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().nth(2), Some("  1.5 KiB"));
+    assert_eq!(output.lines().nth(4), Some("  2.0 MiB"));
+}
+
+#[test]
+fn test_humanized_formats_round_before_picking_the_unit() {
+    assert_eq!(humanize_count(999_950), "1.0M");
+    assert_eq!(humanize_count(-999_950), "-1.0M");
+    assert_eq!(humanize_count(999_949), "999.9K");
+    assert_eq!(humanize_bytes(1024 * 1024 - 1), "1.0 MiB");
+
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Size", 4, Alignment::Right);
+    for size in ["1023.99", "1023.4"] {
+        table.add_row(vec![size.to_string()]);
+    }
+    table.column_mut(0).unwrap().format(Format::Bytes);
+    let lines = table.render_lines();
+    assert_eq!(lines[1], "1.0 KiB");
+    assert_eq!(lines[2], " 1023 B");
+}
+
+#[test]
+fn test_duration_format() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Took", 4, Alignment::Right);
+    for took in ["7985", "PT1H5M", "0.35", "90061", "n/a"] {
+        table.add_row(vec![took.to_string()]);
+    }
+    table.column_mut(0).unwrap().format(Format::Duration {
+        unit: DurationUnit::Seconds,
+    });
+    table.sort_by_value(0).unwrap();
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().skip(1).map(str::trim).collect();
+    assert_eq!(
+        lines,
+        vec!["350ms", "1h 05m 00s", "2h 13m 05s", "1d 01h 01m 01s", "n/a"]
+    );
+
+    table.column_mut(0).unwrap().format(Format::Duration {
+        unit: DurationUnit::Millis,
+    });
+    table.add_row(vec!["2h 13m 05s".to_string()]);
+    table.add_row(vec!["1500".to_string()]);
+    table.sort_by_value(0).unwrap();
+    assert_eq!(table.rows[0][0], "0.35");
+    assert_eq!(table.rows[1][0], "1500");
+    assert_eq!(table.rows[4][0], "PT1H5M");
+    assert_eq!(table.rows[5][0], "2h 13m 05s");
+}
+
+#[test]
+fn test_scientific_and_significant_formats() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Sci", 3, Alignment::Right);
+    table.add_column("Sig", 3, Alignment::Right);
+    for value in ["0.00001234", "1234567", "42.195", "0"] {
+        table.add_row(vec![value.to_string(), value.to_string()]);
+    }
+    table
+        .column_mut(0)
+        .unwrap()
+        .format(Format::Scientific { precision: 2 });
+    table.column_mut(1).unwrap().format(Format::SigFigs(3));
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().skip(1).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "1.23e-05 1.23e-05",
+            "1.23e+06 1.23e+06",
+            "4.22e+01     42.2",
+            "0.00e+00        0",
+        ]
+    );
+}
+
+#[test]
+fn test_locale_separators() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Betrag", 6, Alignment::Right);
+    table.add_column("Amount", 6, Alignment::Right);
+    for (de, en) in [("1.234,5", "1,234.5"), ("2.000", "2,000"), ("0,25", "0.25")] {
+        table.add_row(vec![de.to_string(), en.to_string()]);
+    }
+    table.set_locale(Locale::DE);
+    table.column_mut(1).unwrap().locale(Locale::EN);
+    table
+        .column_mut(0)
+        .unwrap()
+        .format(Format::Fixed { decimals: 2 });
+    table
+        .column_mut(1)
+        .unwrap()
+        .format(Format::Fixed { decimals: 2 });
+
+    assert_eq!(table.aggregate(0, Aggregate::Sum), Ok(3234.75));
+    assert_eq!(table.aggregate(1, Aggregate::Sum), Ok(3234.75));
+    table.set_footer(0, Aggregate::Sum).unwrap();
+    table.sort_by_value(0).unwrap();
+
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[1], "    0,25     0.25");
+    assert_eq!(lines[2], "1.234,50 1,234.50");
+    assert_eq!(lines[4], "3.234,75");
+}
+
+#[test]
+fn test_locale_used_when_reading_cells() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Betrag", 6, Alignment::Right);
+    table.set_locale(Locale::DE);
+    table.column_mut(0).unwrap().validator(Validator::Range {
+        min: 0.0,
+        max: 2000.0,
+    });
+    table.set_strict(true);
+    table.try_add_row(vec!["1.234,5".to_string()]).unwrap();
+    table.try_add_row(vec!["2,5".to_string()]).unwrap();
+    assert!(table.try_add_row(vec!["2.345,5".to_string()]).is_err());
+
+    let large = table.filter_expr("Betrag > 1000").unwrap();
+    assert_eq!(large.rows, vec![vec!["1.234,5"]]);
+    assert_eq!(table.filter_expr("Betrag == 2.5").unwrap().rows.len(), 1);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_machine_output() {
+    let mut table = create_test_table(TableStyle::FancyGrid);
+    table.set_title("People");
+    table.add_row(vec![
+        "Carol Ann".to_string(),
+        "".to_string(),
+        "Oslo".to_string(),
+    ]);
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table
+        .print_with(&mut buffer, &RenderOptions::machine())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buffer.into_inner()).unwrap(),
+        "Name      Age City\n\
+         Alice     30  New_York\n\
+         Bob       25  Los_Angeles\n\
+         Carol_Ann -   Oslo\n"
+    );
+
+    let options = RenderOptions {
+        machine: Some(Machine::Tabs),
+        show_header: false,
+        ..RenderOptions::machine()
+    };
+    let mut buffer = termcolor::NoColor::new(Vec::new());
+    table.print_with(&mut buffer, &options).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    assert_eq!(output.lines().last(), Some("Carol Ann\t\tOslo"));
+}
+
+#[test]
+fn test_machine_fields_round_trip() {
+    let cells = [
+        "",
+        "-",
+        "_",
+        " ",
+        "a b",
+        "a_b",
+        "a\\_b",
+        "\\",
+        "x\ty\nz\r",
+        "\u{a0}",
+        "-1",
+        "a-",
+    ];
+    let mut table = Table::new(TableStyle::Simple);
+    for (i, _) in cells.iter().enumerate() {
+        table.add_column(&format!("C{}", i), 1, Alignment::Left);
+    }
+    table.add_row(cells.iter().map(|c| c.to_string()).collect());
+    let mut output = Vec::new();
+    table
+        .render_with(&mut PlainSink(&mut output), &RenderOptions::machine())
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let line = output.lines().nth(1).unwrap();
+    assert_eq!(
+        line.split_whitespace().collect::<Vec<_>>(),
+        vec![
+            "-",
+            "\\-",
+            "\\_",
+            "_",
+            "a_b",
+            "a\\_b",
+            "a\\\\\\_b",
+            "\\\\",
+            "x\\ty\\nz\\r",
+            "\\u{a0}",
+            "-1",
+            "a-"
+        ]
+    );
+    let decoded: Vec<String> = line.split_whitespace().map(Machine::decode_field).collect();
+    assert_eq!(decoded, cells);
+}
+
+#[test]
+fn test_fixed_width_export() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec![
+        "Zoë".to_string(),
+        "7".to_string(),
+        "Zürich".to_string(),
+    ]);
+    let mut output = Vec::new();
+    table.to_fixed_width(&mut output, &[4, 3, 6]).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Alic 30New Yo\n\
+         Bob  25Los An\n\
+         Zoë  7Züric\n"
+    );
+    assert!(table.to_fixed_width(&mut Vec::new(), &[4]).is_err());
+}
+
+#[test]
+fn test_render_lines() {
+    let table = create_test_table(TableStyle::Grid);
+    let lines = table.render_lines();
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    assert_eq!(lines.join("\n") + "\n", String::from_utf8(output).unwrap());
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[3], "| Alice    |    30 |   New York    |");
+}
+
+#[test]
+fn test_render_iter_matches_render_lines() {
+    for style in [TableStyle::Simple, TableStyle::FancyGrid] {
+        let mut table = create_test_table(style);
+        table.set_title("People");
+        table.set_footer(1, Aggregate::Sum).unwrap();
+        table.set_max_rows(1);
+        let lines: Vec<String> = table.render_iter().collect();
+        assert_eq!(lines, table.render_lines());
+    }
+
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("n", 3, Alignment::Right);
+    for i in 0..1000 {
+        table.add_row(vec![i.to_string()]);
+    }
+    let mut iter = table.render_iter();
+    assert_eq!(iter.nth(3).as_deref(), Some("|   0 |"));
+    assert_eq!(iter.next_row, 1);
+    assert_eq!(iter.count(), 1000);
+}
+
+#[test]
+fn test_render_fmt_into_string() {
+    let table = create_test_table(TableStyle::Grid);
+    let mut rendered = String::from("report:\n");
+    table.render_fmt(&mut rendered).unwrap();
+    assert_eq!(
+        rendered,
+        format!("report:\n{}\n", table.render_lines().join("\n"))
+    );
+}
+
+#[test]
+fn test_fmt_into_indents_lines() {
+    struct Nested(Table);
+
+    impl std::fmt::Display for Nested {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt_into(f)
+        }
+    }
+
+    let table = create_test_table(TableStyle::Simple);
+    let expected: String = table
+        .render_lines()
+        .iter()
+        .map(|line| format!("  {}\n", line))
+        .collect();
+    assert_eq!(format!("{:2}", Nested(table)), expected);
+}
+
+#[test]
+fn test_unicode_styles_fall_back_to_grid_without_feature() {
+    let round = render::style_config(TableStyle::Round).unwrap();
+    let expected = if cfg!(feature = "styles-unicode") {
+        "╭"
+    } else {
+        "+"
+    };
+    assert_eq!(round.top.begin, expected);
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_border_color_wraps_frame_only() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("A", 1, Alignment::Left);
+    table.add_row(vec!["x".to_string()]);
+    let mut spec = ColorSpec::new();
+    spec.set_dimmed(true);
+    table.set_border_color(spec);
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table.print_color(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "\x1b[0m\x1b[2m+---+\x1b[0m");
+    assert_eq!(lines[3], "\x1b[0m\x1b[2m|\x1b[0m x \x1b[0m\x1b[2m|\x1b[0m");
+
+    let mut plain = Vec::new();
+    table.print_to_writer(&mut plain).unwrap();
+    assert_eq!(
+        String::from_utf8(plain).unwrap(),
+        "+---+\n| A |\n+---+\n| x |\n+---+\n"
+    );
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_highlight_row_and_cell() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("A", 1, Alignment::Left);
+    table.add_column("B", 1, Alignment::Left);
+    table.add_row(vec!["a".to_string(), "b".to_string()]);
+    table.add_row(vec!["c".to_string(), "d".to_string()]);
+    let mut bold = ColorSpec::new();
+    bold.set_bold(true);
+    let mut red = ColorSpec::new();
+    red.set_fg(Some(Color::Red));
+    table.highlight_row(1, bold).unwrap();
+    table.highlight_cell(0, 1, red).unwrap();
+
+    let render = |table: &Table| {
+        let mut buffer = termcolor::Buffer::ansi();
+        table.print_color(&mut buffer).unwrap();
+        String::from_utf8(buffer.into_inner()).unwrap()
+    };
+    assert_eq!(
+        render(&table),
+        "A B\na \x1b[0m\x1b[31mb\x1b[0m\n\x1b[0m\x1b[1mc d\n\x1b[0m"
+    );
+    assert_eq!(render(&table.tail(1)), "A B\n\x1b[0m\x1b[1mc d\n\x1b[0m");
+}
+
+#[test]
+fn test_highlight_out_of_range_row() {
+    let mut table = create_test_table(TableStyle::Simple);
+    assert_eq!(
+        table.highlight_row(2, ColorSpec::new()),
+        Err(TableError::RowOutOfRange(2))
+    );
+    assert_eq!(
+        table.highlight_cell(5, "Age", ColorSpec::new()),
+        Err(TableError::RowOutOfRange(5))
+    );
+    assert!(table.row_highlights.is_empty());
+    assert!(table.cell_highlights.is_empty());
+}
+
+#[test]
+fn test_highlights_follow_their_rows_and_columns() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec![
+        "Alice".to_string(),
+        "30".to_string(),
+        "New York".to_string(),
+    ]);
+    let mut red = ColorSpec::new();
+    red.set_fg(Some(Color::Red));
+    table.highlight_row(1, red.clone()).unwrap();
+    table.highlight_cell(1, "City", red.clone()).unwrap();
+    table.highlight_cell(2, "Age", red.clone()).unwrap();
+
+    table
+        .sort_by_cached_key("Age", |age| age.to_string())
+        .unwrap();
+    assert_eq!(table.rows[0][0], "Bob");
+    assert_eq!(table.row_highlights.keys().collect::<Vec<_>>(), vec![&0]);
+    let mut cells: Vec<_> = table.cell_highlights.keys().copied().collect();
+    cells.sort_unstable();
+    assert_eq!(cells, vec![(0, 2), (2, 1)]);
+
+    table.dedup();
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(
+        table.cell_highlights.keys().collect::<Vec<_>>(),
+        vec![&(0, 2)]
+    );
+
+    table.drop_column("Name").unwrap();
+    assert_eq!(
+        table.cell_highlights.keys().collect::<Vec<_>>(),
+        vec![&(0, 1)]
+    );
+    table.drop_column("City").unwrap();
+    assert!(table.cell_highlights.is_empty());
+    assert!(table.row_highlights.contains_key(&0));
+
+    let (mut watcher, updates) = TableWatcher::new(table);
+    updates
+        .send(RowUpdate::Replace(vec![vec!["1".to_string()]]))
+        .unwrap();
+    drop(updates);
+    watcher.run_with(|_| Ok(())).unwrap();
+    assert!(watcher.table().row_highlights.is_empty());
+}
+
+#[test]
+fn test_write_all_spaces_and_aligns_tables() {
+    let mut first = Table::new(TableStyle::Grid);
+    first.add_column("Name", 1, Alignment::Left);
+    first.add_row(vec!["Al".to_string()]);
+    let mut second = Table::new(TableStyle::Grid);
+    second.add_column("Name", 1, Alignment::Left);
+    second.add_row(vec!["Beatrice".to_string()]);
+
+    let mut output = Vec::new();
+    write_all(&mut output, &[&first, &second], true).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(
+        output,
+        "+----------+\n| Name     |\n+----------+\n| Al       |\n+----------+\n\n\
+         +----------+\n| Name     |\n+----------+\n| Beatrice |\n+----------+\n"
+    );
+
+    let options = first.render_options().trailing_newlines(2);
+    let mut sink = render::LineSink::default();
+    first.render_with(&mut sink, &options).unwrap();
+    assert_eq!(sink.lines[sink.lines.len() - 2..], ["", ""]);
+}
+
+#[test]
+fn test_layout_unify_lines_up_tables() {
+    let mut tables: Vec<Table> = [("x", "1"), ("longer", "12345")]
+        .iter()
+        .map(|(name, value)| {
+            let mut table = Table::new(TableStyle::Simple);
+            table.add_column("Name", 1, Alignment::Left);
+            table.add_column("Value", 1, Alignment::Right);
+            table.add_row(vec![name.to_string(), value.to_string()]);
+            table
+        })
+        .collect();
+    let mut refs: Vec<&mut Table> = tables.iter_mut().collect();
+    Layout::unify(&mut refs);
+
+    assert_eq!(tables[0].render_lines(), ["Name   Value", "x          1"]);
+    assert_eq!(tables[1].render_lines(), ["Name   Value", "longer 12345"]);
+    assert_eq!(
+        tables[0].compute_layout().column_widths,
+        tables[1].compute_layout().column_widths
+    );
+}
+
+#[test]
+fn test_display_width_matches_renderer() {
+    assert_eq!(display_width("abc"), 3);
+    assert_eq!(display_width("日本"), 4);
+    assert_eq!(display_width("e\u{301}"), 1);
+
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("A", 1, Alignment::Left);
+    table.add_row(vec!["日本".to_string()]);
+    let top = &table.render_lines()[0];
+    assert_eq!(top.len(), display_width("日本") + 4);
+}
+
+#[test]
+fn test_preview_styles_lists_every_style() {
+    let mut output = Vec::new();
+    preview_styles(&mut output, None).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    for style in TableStyle::ALL {
+        assert!(output.contains(&format!("{:?}:\n", style)));
+    }
+    assert!(output.starts_with("Simple:\nName"));
+
+    let table = create_test_table(TableStyle::Grid);
+    let mut output = Vec::new();
+    preview_styles(&mut output, Some(&table)).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Markdown:\n\n| Name     |   Age |"));
+}
+
+#[test]
+fn test_parse_style_and_alignment() {
+    assert_eq!("fancy-grid".parse(), Ok(TableStyle::FancyGrid));
+    assert_eq!("Fancy_Grid".parse(), Ok(TableStyle::FancyGrid));
+    assert!(matches!("right".parse(), Ok(Alignment::Right)));
+    for style in TableStyle::ALL {
+        assert_eq!(style.to_string().parse(), Ok(style));
+    }
+
+    let error = "fancy".parse::<TableStyle>().unwrap_err();
+    assert_eq!(error.expected().len(), TableStyle::ALL.len());
+    assert!(error
+        .to_string()
+        .starts_with("unknown style 'fancy', expected one of: simple, grid, fancy-grid,"));
+    let Err(error) = "middle".parse::<Alignment>() else {
+        panic!("middle is not an alignment");
+    };
+    assert_eq!(
+        error.to_string(),
+        "unknown alignment 'middle', expected one of: left, center, right"
+    );
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn test_clap_value_enum() {
+    use clap::ValueEnum;
+    assert_eq!(
+        TableStyle::from_str("fancy-grid", false),
+        Ok(TableStyle::FancyGrid)
+    );
+    assert!(matches!(
+        Alignment::from_str("CENTER", true),
+        Ok(Alignment::Center)
+    ));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_project_round_trip() {
+    let mut table = create_test_table(TableStyle::Round);
+    table.set_title("People");
+    table.set_footer(1, Aggregate::Sum).unwrap();
+    table.set_locale(Locale::DE);
+    table.set_max_rows(5);
+    table
+        .column_mut(1)
+        .unwrap()
+        .format(Format::Fixed { decimals: 1 })
+        .header_alignment(Alignment::Center);
+    table
+        .column_mut(2)
+        .unwrap()
+        .wrap(Wrap::Hybrid { threshold: 4 })
+        .truncate(Truncate::Middle)
+        .pinned(true);
+
+    let mut saved = Vec::new();
+    table.write_project(&mut saved).unwrap();
+    let loaded = Table::from_project_reader(saved.as_slice()).unwrap();
+    assert_eq!(loaded.render_lines(), table.render_lines());
+
+    let mut resaved = Vec::new();
+    loaded.write_project(&mut resaved).unwrap();
+    assert_eq!(resaved, saved);
+
+    let Err(error) = Table::from_project_reader(&b"{\"tabprinter\": 99}"[..]) else {
+        panic!("newer project versions are rejected");
+    };
+    assert_eq!(error.to_string(), "unsupported project version 99");
+
+    let saved = String::from_utf8(saved).unwrap();
+    assert!(saved.contains("\"aggregate\": \"sum\""));
+    assert!(saved.contains("\"truncate\": \"middle\""));
+    assert!(saved.contains("\"header_orientation\": \"horizontal\""));
+    let numeric = saved.replacen("\"Alice\"", "42", 1);
+    let Err(error) = Table::from_project_reader(numeric.as_bytes()) else {
+        panic!("cells that are not strings are rejected");
+    };
+    assert_eq!(error.to_string(), "row 0: expected a string, found 42");
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_link_template_wraps_cell_text() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Issue", 6, Alignment::Left);
+    table
+        .column_mut(0)
+        .unwrap()
+        .link_template("https://issues.example.com/{value}");
+    table.add_row(vec!["A 1".to_string()]);
+    table.add_row(vec![String::new()]);
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table.print_color(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    assert_eq!(
+        output.lines().nth(1).unwrap(),
+        "\x1b]8;;https://issues.example.com/A%201\x1b\\A 1\x1b]8;;\x1b\\"
+    );
+    assert_eq!(output.lines().nth(2).unwrap(), "");
+    assert_eq!(table.render_lines()[1], "A 1");
+}
+
+#[test]
+fn test_html_export_with_tooltips() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.set_title("People & places");
+    table.set_cell_tooltip(1, 2, "City of \"Angels\"").unwrap();
+    table.set_cell_data(1, 2, "State", "CA").unwrap();
+    table.set_cell_data(0, 0, "id", "7").unwrap();
+
+    let html = table.to_html();
+    assert!(html.starts_with(
+        "<table>\n<caption>People &amp; places</caption>\n\
+         <thead>\n<tr><th>Name</th><th>Age</th><th>City</th></tr>\n</thead>\n"
+    ));
+    assert!(
+        html.contains("<tr><td data-id=\"7\">Alice</td><td style=\"text-align: right\">30</td>")
+    );
+    assert!(html.contains(
+        "<td style=\"text-align: center\" title=\"City of &quot;Angels&quot;\" \
+         data-state=\"CA\">Los Angeles</td></tr>"
+    ));
+
+    let tail = table.tail(1).to_html();
+    assert!(tail.contains("title=\"City of &quot;Angels&quot;\""));
+    assert!(!tail.contains("data-id"));
+}
+
+#[test]
+fn test_html_cell_meta_out_of_range_row() {
+    let mut table = create_test_table(TableStyle::Grid);
+    assert_eq!(
+        table.set_cell_tooltip(2, "City", "Nowhere"),
+        Err(TableError::RowOutOfRange(2))
+    );
+    assert_eq!(
+        table.set_cell_data(7, 0, "id", "7"),
+        Err(TableError::RowOutOfRange(7))
+    );
+    assert!(!table.to_html().contains("title="));
+    assert!(!table.to_html().contains("data-id"));
+}
+
+#[test]
+fn test_html_tooltips_follow_sorting_and_dropped_columns() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.set_cell_tooltip(1, "City", "West coast").unwrap();
+    table
+        .sort_by_cached_key("Name", |name| std::cmp::Reverse(name.to_string()))
+        .unwrap();
+    assert!(table.to_html().contains(
+        "<tr><td>Bob</td><td style=\"text-align: right\">25</td>\
+                   <td style=\"text-align: center\" title=\"West coast\">Los Angeles</td></tr>"
+    ));
+    table.drop_column("Name").unwrap();
+    assert!(table
+        .to_html()
+        .contains("title=\"West coast\">Los Angeles</td>"));
+    table.drop_column("City").unwrap();
+    assert!(!table.to_html().contains("title="));
+}
+
+#[test]
+fn test_columns_addressable_by_name() {
+    let mut table = create_test_table(TableStyle::Simple);
+    assert_eq!(table.aggregate("Age", Aggregate::Sum), Ok(55.0));
+    assert_eq!(
+        table.aggregate("Years", Aggregate::Sum),
+        Err(TableError::ColumnNotFound("Years".to_string()))
+    );
+    assert_eq!(table.value_counts("City").unwrap().rows.len(), 2);
+    table
+        .sort_by_cached_key("Age", |cell| cell.to_string())
+        .unwrap();
+    assert_eq!(table.rows[0][0], "Bob");
+    table.set_footer("Age", Aggregate::Max).unwrap();
+    assert_eq!(table.footer_cell(1).as_deref(), Some("30"));
+
+    table.add_column("Age", 3, Alignment::Right);
+    assert_eq!(
+        table.column_index("Age"),
+        Err(TableError::AmbiguousColumn("Age".to_string()))
+    );
+    assert_eq!(
+        table
+            .aggregate("Age", Aggregate::Sum)
+            .unwrap_err()
+            .to_string(),
+        "more than one column is named \"Age\""
+    );
+}
+
+#[test]
+fn test_column_lookups_report_missing_names() {
+    let mut table = create_test_table(TableStyle::Simple);
+    let missing = || Err(TableError::ColumnNotFound("Missing".to_string()));
+    assert_eq!(table.split_by("Missing").err(), missing().err());
+    assert_eq!(table.value_counts("Missing").err(), missing().err());
+    assert_eq!(table.set_footer("Missing", Aggregate::Sum), missing());
+    assert_eq!(table.dedup_by_column("Missing"), missing());
+    assert_eq!(
+        table.highlight_cell(0, "Missing", ColorSpec::new()),
+        missing()
+    );
+    assert!(table.column_mut("Missing").is_err());
+    table.add_column("Age", 3, Alignment::Right);
+    assert_eq!(
+        table.sort_by_cached_key("Age", |cell| cell.to_string()),
+        Err(TableError::AmbiguousColumn("Age".to_string()))
+    );
+}
+
+#[test]
+fn test_row_views_look_up_cells_by_header() {
+    let mut table = create_test_table(TableStyle::Simple);
+    let adults = table.filter_rows(|row| row.get_i64("Age").is_some_and(|age| age >= 30));
+    assert_eq!(adults.rows.len(), 1);
+    assert_eq!(table.row_view(0).unwrap().get_str("City"), Some("New York"));
+    assert!(table.row_view(0).unwrap().get("Years").is_none());
+
+    table.sort_rows_by(|a, b| a.get_i64("Age").cmp(&b.get_i64("Age")));
+    assert_eq!(table.rows[0][0], "Bob");
+
+    table.add_computed_column_with("Next", |row| {
+        assert!(row.get("Next").is_none());
+        (row.get_i64("Age").unwrap_or_default() + 1).to_string()
+    });
+    table.add_row(vec![
+        "Dana".to_string(),
+        "1,204".to_string(),
+        "Rome".to_string(),
+    ]);
+    let ages: Vec<&str> = table.row_views().filter_map(|row| row.get_str(3)).collect();
+    assert_eq!(ages.last(), Some(&"1205"));
+    let cell = table.row_view(0).unwrap().get("Next").unwrap();
+    assert_eq!((cell.header(), cell.as_f64()), ("Next", Some(26.0)));
+}
+
+#[test]
+fn test_column_values_parse_cells() {
+    let mut table = create_test_table(TableStyle::Simple);
+    assert_eq!(table.column_values::<i64>("Age"), Ok(vec![30, 25]));
+    assert_eq!(table.column_values::<f64>(1), Ok(vec![30.0, 25.0]));
+    assert_eq!(
+        table.column_values::<String>("Name").unwrap(),
+        vec!["Alice", "Bob"]
+    );
+    table.add_row(vec![
+        "Carol".to_string(),
+        "n/a".to_string(),
+        "Rome".to_string(),
+    ]);
+    assert_eq!(
+        table.column_values::<i64>("Age").unwrap_err().to_string(),
+        "row 2, column 1: \"n/a\" is invalid, expected a whole number"
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_column_values_reads_dates() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Due", 10, Alignment::Left);
+    table.add_row(vec!["2024-02-29".to_string()]);
+    let dates = table.column_values::<chrono::NaiveDate>("Due").unwrap();
+    assert_eq!(
+        dates,
+        vec![chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()]
+    );
+}
+
+#[test]
+fn test_raw_column_passes_escapes_through() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("State", 5, Alignment::Left);
+    table.add_column("Note", 4, Alignment::Left);
+    table.column_mut("State").unwrap().raw(true);
+    table.add_row(vec!["\x1b[31mfailed\x1b[0m".to_string(), "x".to_string()]);
+    table.add_row(vec!["ok".to_string(), "y".to_string()]);
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("| \x1b[31mfailed\x1b[0m | x    |"));
+    assert!(output.contains("| ok     | y    |"));
+    assert_eq!(visible_width("\x1b]8;;http://a\x1b\\a\x1b]8;;\x1b\\"), 1);
+    assert_eq!(visible_width("\x1b[31mfailed\x1b[0m"), 6);
+}
+
+#[cfg(feature = "styles-unicode")]
+#[test]
+fn test_wide_glyph_override_keeps_border_length() {
+    assert!(TableStyle::Block.glyph_widths().contains(&('■', 1)));
+    let mut table = create_test_table(TableStyle::Block);
+    let mut narrow = Vec::new();
+    table.print_to_writer(&mut narrow).unwrap();
+    table.set_glyph_width('■', 2);
+    let mut wide = Vec::new();
+    table.print_to_writer(&mut wide).unwrap();
+    let (narrow, wide) = (
+        String::from_utf8(narrow).unwrap(),
+        String::from_utf8(wide).unwrap(),
+    );
+    let top = |output: &str| output.lines().next().unwrap().to_string();
+    let drawn = |line: &str| {
+        line.chars()
+            .map(|c| if c == '■' { 2 } else { 1 })
+            .sum::<usize>()
+    };
+    assert_eq!(drawn(&top(&wide)), display_width(&top(&narrow)));
+    assert_eq!(wide.lines().nth(1), narrow.lines().nth(1));
+}
+
+#[test]
+fn test_term_caps_from_environment() {
+    let env = |pairs: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            pairs
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    let caps = TermCaps::from_env(
+        true,
+        Some((100, 40)),
+        env(&[
+            ("TERM", "xterm-256color"),
+            ("LANG", "de_DE.UTF-8"),
+            ("TERM_PROGRAM", "WezTerm"),
+        ]),
+    );
+    assert_eq!(caps.colors, ColorDepth::Ansi256);
+    assert!(caps.unicode && caps.hyperlinks);
+
+    let caps = TermCaps::from_env(
+        true,
+        Some((60, 20)),
+        env(&[("NO_COLOR", "1"), ("LANG", "C")]),
+    );
+    assert_eq!(caps.colors, ColorDepth::None);
+    let options = RenderOptions::new(TableStyle::Round).for_terminal(&caps);
+    assert_eq!(options.charset, Charset::Ascii);
+    assert!(!options.color && !options.hyperlinks);
+    assert_eq!(options.max_width, Some(60));
+
+    let piped = TermCaps::from_env(false, None, env(&[("COLORTERM", "truecolor")]));
+    assert_eq!(piped.colors, ColorDepth::None);
+}
+
+#[test]
+fn test_fast_path_matches_general_renderer() {
+    let mut table = create_test_table(TableStyle::Simple);
+    table.add_row(vec![
+        "日本".to_string(),
+        "7".to_string(),
+        "Kyoto".to_string(),
+    ]);
+    table.set_max_rows(2);
+    table
+        .column_mut("Age")
+        .unwrap()
+        .header_alignment(Alignment::Center);
+    let options = table.render_options().trailing_newlines(1);
+    assert!(table.fast_path(&options));
+
+    let mut fast = Vec::new();
+    table.write_fast(&mut fast, &options).unwrap();
+    let mut general = Vec::new();
+    table
+        .render_with(&mut render::PlainSink(&mut general), &options)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(fast).unwrap(),
+        String::from_utf8(general).unwrap()
+    );
+
+    table.add_row(vec![
+        "two\nlines".to_string(),
+        "1".to_string(),
+        "x".to_string(),
+    ]);
+    table.set_max_rows(10);
+    assert!(!table.fast_path(&table.render_options()));
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_csv_backed_table_reads_pages_on_demand() {
+    let data = "id,name\n1,a\n2,\"b\nc\"\n3,c\n4\n5,e\n";
+    let mut csv =
+        CsvBackedTable::from_reader(io::Cursor::new(data), CsvDialect::default()).unwrap();
+    assert_eq!(csv.headers(), ["id", "name"]);
+    assert_eq!((csv.len(), csv.page_count(2)), (5, 3));
+
+    let page = csv.page(1, 2).unwrap();
+    assert_eq!(page.rows, vec![vec!["3", "c"], vec!["4", ""]]);
+    assert_eq!(csv.rows(1..2).unwrap(), vec![vec!["2", "b\nc"]]);
+    assert_eq!(csv.page(2, 2).unwrap().rows, vec![vec!["5", "e"]]);
+    assert!(csv.page(3, 2).unwrap().rows.is_empty());
+
+    let file = CsvBackedTable::open("examples/data.csv").unwrap();
+    assert_eq!(
+        file.len(),
+        Table::from_csv("examples/data.csv").unwrap().rows.len()
+    );
+}
+
+#[test]
+fn test_column_widths_follow_added_and_removed_rows() {
+    let mut table = create_test_table(TableStyle::Grid);
+    let widths = |table: &Table| table.compute_layout().column_widths;
+    assert_eq!(widths(&table), vec![8, 5, 13]);
+    table.add_row(vec![
+        "Maximilian".to_string(),
+        "7".to_string(),
+        "Ulm".to_string(),
+    ]);
+    assert_eq!(widths(&table), vec![10, 5, 13]);
+    table.highlight_row(2, ColorSpec::new()).unwrap();
+    assert_eq!(table.remove_row(0)[0], "Alice");
+    assert!(table.row_highlights.contains_key(&1));
+    assert_eq!(widths(&table), vec![10, 5, 13]);
+    table.remove_row(1);
+    assert_eq!(widths(&table), vec![8, 5, 13]);
+    table
+        .column_mut("Age")
+        .unwrap()
+        .format(Format::Fixed { decimals: 6 });
+    assert_eq!(widths(&table), vec![8, 9, 13]);
+}
+
+#[test]
+fn test_column_widths_follow_sorting() {
+    let render = |table: &Table| {
+        let mut buffer = Vec::new();
+        table.print_to_writer(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    };
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("N", 0, Alignment::Left);
+    table.add_row(vec!["1".to_string()]);
+    table.add_row(vec!["200000000000".to_string()]);
+    table.set_max_rows(1);
+    assert!(render(&table).contains("| 1 |"));
+    table
+        .sort_by_cached_key(0, |cell| std::cmp::Reverse(cell.len()))
+        .unwrap();
+    assert!(render(&table).contains("| 200000000000 |"));
+    table.sort_by_value(0).unwrap();
+    assert!(render(&table).contains("| 1 |"));
+    table.sort_rows_by(|a, b| b.get_str(0).cmp(&a.get_str(0)));
+    assert!(render(&table).contains("| 200000000000 |"));
+}
+
+#[test]
+fn test_trailing_spaces_trimmed_for_borderless_styles() {
+    let lines = |table: &Table| {
+        let mut buffer = Vec::new();
+        table.print_to_writer(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    };
+    let mut table = create_test_table(TableStyle::Simple);
+    assert!(lines(&table).lines().all(|line| !line.ends_with(' ')));
+    assert!(table.render_lines().iter().all(|line| !line.ends_with(' ')));
+    table.set_trim_trailing_spaces(false);
+    assert!(lines(&table).lines().any(|line| line.ends_with(' ')));
+
+    let grid = create_test_table(TableStyle::Grid);
+    assert!(!grid.render_options().trim_trailing_spaces);
+    assert!(lines(&grid).lines().all(|line| line.ends_with(['+', '|'])));
+}
+
+#[test]
+fn test_empty_policy() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Name", 6, Alignment::Left);
+    let print = |table: &Table| {
+        let mut buffer = Vec::new();
+        table.print_to_writer(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    };
+    assert!(print(&table).contains("Name"));
+
+    table.set_empty_policy(EmptyPolicy::ShowMessage("no results".to_string()));
+    assert_eq!(print(&table), "no results\n");
+    assert_eq!(table.render_iter().collect::<Vec<_>>(), ["no results"]);
+
+    table.set_empty_policy(EmptyPolicy::PrintNothing);
+    assert_eq!(print(&table), "");
+    table.style = TableStyle::Simple;
+    assert_eq!(print(&table), "");
+
+    table.add_row(vec!["Ada".to_string()]);
+    assert!(print(&table).contains("Ada"));
+}
+
+#[test]
+fn test_right_to_left_mirrors_columns() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Name", 5, Alignment::Left);
+    table.add_column("Qty", 4, Alignment::Right);
+    table.add_row(vec!["Ada".to_string(), "7".to_string()]);
+    table.set_right_to_left(true);
+    assert_eq!(
+        table.render_lines(),
+        [
+            "+------+-------+",
+            "| Qty  |  Name |",
+            "+------+-------+",
+            "| 7    |   Ada |",
+            "+------+-------+",
+        ]
+    );
+    assert_eq!(
+        table.render_iter().collect::<Vec<_>>(),
+        table.render_lines()
+    );
+
+    let mut simple = create_test_table(TableStyle::Simple);
+    simple.set_right_to_left(true);
+    let mut buffer = Vec::new();
+    simple.print_to_writer(&mut buffer).unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "    City     Age     Name\n  New York   30     Alice\nLos Angeles  25       Bob\n"
+    );
+}
+
+#[test]
+fn test_repeat_header_every() {
+    let mut table = numbered_table(5);
+    table.repeat_header_every(2);
+    assert_eq!(
+        table.render_lines(),
+        ["  N", "  0", "  1", "  N", "  2", "  3", "  N", "  4"]
+    );
+    assert_eq!(table.render_viewport(0, 3), ["  N", "  0", "  1"]);
+
+    let mut grid = numbered_table(3);
+    grid.style = TableStyle::Grid;
+    grid.repeat_header_every(2);
+    assert_eq!(
+        grid.render_lines(),
+        [
+            "+------+", "|    N |", "+------+", "|    0 |", "|    1 |", "+------+", "|    N |",
+            "+------+", "|    2 |", "+------+",
+        ]
+    );
+    assert_eq!(grid.render_iter().collect::<Vec<_>>(), grid.render_lines());
+}
+
+#[test]
+fn test_watcher_marks_changed_cells() {
+    let (mut watcher, updates) = TableWatcher::new(numbered_table(2));
+    watcher.set_debounce(std::time::Duration::from_millis(1));
+    let mut red = ColorSpec::new();
+    red.set_fg(Some(Color::Red));
+    watcher.mark_changes(red.clone(), 2);
+    let sender = std::thread::spawn(move || {
+        for rows in [["0", "5"], ["0", "5"], ["0", "5"]] {
+            let rows = rows.iter().map(|cell| vec![cell.to_string()]).collect();
+            updates.send(RowUpdate::Replace(rows)).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    });
+
+    let mut marked = Vec::new();
+    watcher
+        .run_with(|table| {
+            marked.push(table.cell_highlights.get(&(1, 0)).cloned());
+            assert!(!table.cell_highlights.contains_key(&(0, 0)));
+            Ok(())
+        })
+        .unwrap();
+    sender.join().unwrap();
+    assert_eq!(marked, vec![Some(red.clone()), Some(red), None]);
+}
+
+#[test]
+fn test_column_units() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Latency", 1, Alignment::Right);
+    table.add_column("Size", 1, Alignment::Right);
+    for (latency, size) in [("120", "2048"), ("7", "")] {
+        table.add_row(vec![latency.to_string(), size.to_string()]);
+    }
+    table.column_mut("Latency").unwrap().unit("ms");
+    table.column_mut("Size").unwrap().unit_in_header("KiB");
+    assert_eq!(
+        table.render_lines(),
+        ["Latency Size (KiB)", " 120 ms       2048", "   7 ms"]
+    );
+    table.sort_by_value("Latency").unwrap();
+    assert_eq!(table.rows[0][0], "7");
+}
+
+#[test]
+fn test_unit_change_after_render_resizes_column() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("Took", 1, Alignment::Right);
+    table.add_row(vec!["12345".to_string()]);
+    assert_eq!(table.render_lines()[3], "| 12345 |");
+    table.column_mut(0).unwrap().unit("ms");
+    assert_eq!(table.render_lines()[3], "| 12345 ms |");
+    table.column_mut(0).unwrap().unit_in_header("ms");
+    assert_eq!(table.render_lines()[3], "|     12345 |");
+}
+
+#[test]
+fn test_numbers_lose_precision_before_digits() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("V", 1, Alignment::Right);
+    table.add_column("Id", 1, Alignment::Right);
+    for (value, id) in [("3.14159265", "1234567890"), ("123456.789", "7")] {
+        table.add_row(vec![value.to_string(), id.to_string()]);
+    }
+    let mut options = table.render_options();
+    options.max_width = Some(13);
+    let mut output = Vec::new();
+    table
+        .render_with(&mut render::PlainSink(&mut output), &options)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "    V     Id\n3.142 12345…\n1e+05      7\n"
+    );
+}
+
+#[test]
+fn test_column_descriptions_in_exports() {
+    let mut table = Table::new(TableStyle::Grid);
+    table.add_column("RTT", 4, Alignment::Right);
+    table.add_column("Host", 6, Alignment::Left);
+    table.add_row(vec!["12".to_string(), "web1".to_string()]);
+    table
+        .column_mut("RTT")
+        .unwrap()
+        .description("Round trip time in \"ms\"");
+    let plain = table.render_lines();
+    assert!(plain.iter().all(|line| !line.contains("Round")));
+    assert!(table
+        .to_html()
+        .contains("<th title=\"Round trip time in &quot;ms&quot;\">RTT</th><th>Host</th>"));
+    assert_eq!(
+        table.to_markdown(),
+        "| RTT[^1] | Host |\n| ---: | :--- |\n| 12 | web1 |\n\n[^1]: Round trip time in \"ms\"\n"
+    );
+
+    #[cfg(feature = "tui")]
+    {
+        use crate::interact::Session;
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let mut session = Session::new(&table);
+        session.handle(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE));
+        let help = session.help_lines();
+        assert_eq!(
+            &help[help.len() - 2..],
+            ["RTT   Round trip time in \"ms\"", "Host"]
+        );
+    }
+}
+
+#[cfg(feature = "styles-unicode")]
+#[test]
+fn test_compact_style_drops_padding_and_outer_borders() {
+    let table = create_test_table(TableStyle::Compact);
+    assert_eq!(
+        table.render_lines(),
+        [
+            "Name    │  Age│    City",
+            "────────┼─────┼─────────────",
+            "Alice   │   30│  New York",
+            "Bob     │   25│ Los Angeles",
+        ]
+    );
+    assert_eq!(
+        table.render_iter().collect::<Vec<_>>(),
+        table.render_lines()
+    );
+    let minimal = create_test_table(TableStyle::Minimal);
+    assert_eq!(
+        minimal.compute_layout().total_width,
+        table.compute_layout().total_width + 2 + 2 * 3
+    );
+}
+
+#[test]
+fn test_index_column_formats() {
+    assert_eq!(IndexFormat::ZeroPadded { width: 3 }.label(17), "017");
+    assert_eq!(IndexFormat::Alphabetic.label(28), "ab");
+    assert_eq!(IndexFormat::Roman.label(1994), "MCMXCIV");
+    assert_eq!(IndexFormat::Roman.label(0), "0");
+
+    let mut table = numbered_table(3);
+    table.show_index(IndexFormat::Alphabetic);
+    table.set_index_start(26);
+    assert_eq!(
+        table.render_lines(),
+        [" #   N", " z   0", "aa   1", "ab   2"]
+    );
+    assert_eq!(table.rows[0], ["0"]);
+
+    #[cfg(feature = "csv")]
+    {
+        let mut file = CsvBackedTable::open("examples/data.csv").unwrap();
+        file.show_index(IndexFormat::ZeroPadded { width: 3 });
+        let lines = file.page(1, 2).unwrap().render_lines();
+        assert!(lines[1].starts_with("003 "));
+        assert!(lines[2].starts_with("004 "));
+    }
+}
+
+#[test]
+fn test_upsert_by_key() {
+    let mut table = Table::new(TableStyle::Simple);
+    table.add_column("Host", 4, Alignment::Left);
+    table.add_column("Load", 4, Alignment::Right);
+    let row = |host: &str, load: &str| vec![host.to_string(), load.to_string()];
+    assert_eq!(table.upsert_by_key("Host", row("web1", "0.5")), Ok(None));
+    assert_eq!(table.upsert_by_key("Host", row("web2", "1.25")), Ok(None));
+    let widths = |table: &Table| table.compute_layout().column_widths;
+    assert_eq!(widths(&table), vec![4, 4]);
+
+    assert_eq!(
+        table.upsert_by_key("Host", row("web1", "12.75")),
+        Ok(Some(row("web1", "0.5")))
+    );
+    assert_eq!(table.rows, vec![row("web1", "12.75"), row("web2", "1.25")]);
+    assert_eq!(widths(&table), vec![4, 5]);
+    assert!(table.upsert_by_key("Missing", row("web3", "1")).is_err());
+    assert!(table
+        .upsert_by_key("Host", vec!["web3".to_string()])
+        .is_err());
+}
+
+#[test]
+fn test_lock_widths_keeps_columns_for_later_rows() {
+    let mut table = numbered_table(2);
+    table.lock_widths();
+    table.add_row(vec!["123456".to_string()]);
+    let mut output = Vec::new();
+    table.print_to_writer(&mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "  N\n  0\n  1\n12…\n");
+
+    table.unlock_widths();
+    assert_eq!(table.render_lines()[3], "123456");
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn test_border_painter_recolors_and_replaces_segments() {
+    struct Stripes;
+    impl BorderPainter for Stripes {
+        fn color(&self, segment: &BorderSegment) -> Option<ColorSpec> {
+            let mut spec = ColorSpec::new();
+            match segment.kind {
+                BorderKind::Row(row) if row % 2 == 1 => spec.set_fg(Some(Color::Blue)),
+                _ => return None,
+            };
+            Some(spec)
+        }
+
+        fn glyphs(&self, segment: &BorderSegment) -> Option<String> {
+            match (segment.kind, segment.position) {
+                (BorderKind::Top, BorderPosition::Separator(_)) => Some("v".to_string()),
+                (BorderKind::Bottom, BorderPosition::Rule(column)) => {
+                    Some(column.to_string().repeat(segment.glyphs.len()))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    let mut table = create_test_table(TableStyle::Grid);
+    table.set_border_painter(Stripes);
+    let lines = table.render_lines();
+    assert_eq!(lines[0], "+----------v-------v---------------+");
+    assert_eq!(lines[5], "+0000000000+1111111+222222222222222+");
+
+    let mut buffer = termcolor::Buffer::ansi();
+    table.print_color(&mut buffer).unwrap();
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    let bob = output.lines().find(|line| line.contains("Bob")).unwrap();
+    assert!(bob.starts_with("\x1b[0m\x1b[34m|\x1b[0m"));
+    let alice = output.lines().find(|line| line.contains("Alice")).unwrap();
+    assert!(alice.starts_with("| Alice"));
+}
+
+#[test]
+fn test_exporter_registry() {
+    let table = create_test_table(TableStyle::Grid);
+    let mut exporters = Exporters::new();
+    exporters.register("names", |table: &Table, writer: &mut dyn Write| {
+        for row in &table.rows {
+            writeln!(writer, "{}", row[0])?;
+        }
+        Ok(())
+    });
+    let export = |name: &str| {
+        let mut output = Vec::new();
+        exporters
+            .export(name, &table, &mut output)
+            .map(|_| String::from_utf8(output).unwrap())
+    };
+    assert_eq!(export("names").unwrap(), "Alice\nBob\n");
+    assert_eq!(export("markdown").unwrap(), table.to_markdown());
+    assert!(exporters.names().any(|name| name == "html"));
+    let error = export("yaml").unwrap_err();
+    assert!(error.to_string().contains("unknown format `yaml`"));
+
+    #[cfg(feature = "csv")]
+    assert!(export("csv")
+        .unwrap()
+        .starts_with("Name,Age,City\nAlice,30,New York\n"));
+    #[cfg(feature = "json")]
+    {
+        let json = export("json").unwrap();
+        let back = Table::from_json_reader(json.as_bytes()).unwrap();
+        assert_eq!(back.rows, table.rows);
+    }
+}
+
+#[test]
+fn test_importer_registry() {
+    let mut importers = Importers::new();
+    importers.register(
+        "lines",
+        |reader: &mut dyn std::io::Read, options: &ImportOptions| {
+            let mut text = String::new();
+            reader.read_to_string(&mut text)?;
+            let header = options.extra.get("header").map_or("Line", String::as_str);
+            let mut table = Table::new(TableStyle::Simple);
+            table.add_column(header, 0, Alignment::Left);
+            for line in text.lines() {
+                table.add_row(vec![line.to_string()]);
+            }
+            Ok(table)
+        },
+    );
+    let mut options = ImportOptions::default();
+    options
+        .extra
+        .insert("header".to_string(), "Word".to_string());
+    let table = importers
+        .import("lines", &mut "one\ntwo\n".as_bytes(), &options)
+        .unwrap();
+    assert_eq!(table.columns[0].header, "Word");
+    assert_eq!(table.rows, vec![vec!["one"], vec!["two"]]);
+    assert!(importers.names().any(|name| name == "lines"));
+    assert!(Table::import("lines", "".as_bytes(), &options).is_err());
+
+    let error = Table::import("yaml", "".as_bytes(), &options)
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("unknown format `yaml`"));
+
+    #[cfg(feature = "csv")]
+    {
+        let table = Table::import("tsv", "A\tB\n1\t2\n".as_bytes(), &options).unwrap();
+        assert_eq!(table.rows, vec![vec!["1", "2"]]);
+    }
+    #[cfg(feature = "json")]
+    {
+        let table = Table::import("json", r#"[{"A": "1"}]"#.as_bytes(), &options).unwrap();
+        assert_eq!(table.rows, vec![vec!["1"]]);
+    }
+}
+
+#[test]
+fn test_pages_with_subtotals_and_grand_total() {
+    let mut table = numbered_table(5);
+    table.set_footer("N", Aggregate::Sum).unwrap();
+    let render = |page: &Table| {
+        let mut output = Vec::new();
+        page.print_to_writer(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let pages = table.pages(&PaginationOptions {
+        rows_per_page: 2,
+        page_subtotals: false,
+    });
+    assert_eq!(pages.len(), 3);
+    assert_eq!(render(&pages[0]), "  N\n  0\n  1\n");
+    assert_eq!(render(&pages[2]), "  N\n  4\n 10\n");
+
+    let pages = table.pages(&PaginationOptions {
+        rows_per_page: 2,
+        page_subtotals: true,
+    });
+    assert_eq!(render(&pages[0]), "  N\n  0\n  1\n  1\n");
+    assert_eq!(render(&pages[1]), "  N\n  2\n  3\n  5\n");
+    assert_eq!(render(&pages[2]), "  N\n  4\n  4\n 10\n");
+    assert!(pages[2]
+        .to_html()
+        .contains("<tfoot>\n<tr><td style=\"text-align: right\">4</td></tr>\n<tr>"));
+}
+
+#[cfg(feature = "tui")]
+#[test]
+fn test_interactive_page_jumps() {
+    use crate::interact::Session;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    let table = numbered_table(20);
+    let mut session = Session::new(&table);
+    session.resize(6);
+    for c in [':', '3', '7'] {
+        session.handle(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    assert_eq!(session.status(), ":37");
+    session.handle(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(session.offset, 16);
+    assert!(session
+        .status()
+        .starts_with("rows 17-20 of 20, page 5 of 5 |"));
+
+    for code in [KeyCode::Char(':'), KeyCode::Char('2'), KeyCode::Enter] {
+        session.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+    assert_eq!(session.page_position(), (2, 5));
+    session.handle(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE));
+    assert_eq!(session.offset, 16);
+    session.handle(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE));
+    assert_eq!(session.offset, 0);
+}
+
+#[test]
+fn test_record_shows_one_row_as_fields() {
+    let mut table = create_test_table(TableStyle::Grid);
+    table.columns[1].unit("years");
+    let record = table.record(1).unwrap();
+    assert_eq!(record.style, TableStyle::Grid);
+    assert_eq!(
+        record.rows,
+        vec![
+            vec!["Name", "Bob"],
+            vec!["Age", "25 years"],
+            vec!["City", "Los Angeles"],
+        ]
+    );
+    assert!(table.record(2).is_none());
+    let error = table.print_record(2).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), "row index 2 is out of range");
+}
+
+#[test]
+fn test_fast_path_matches_general_renderer_under_every_option() {
+    type Tweak = fn(&mut Table, &mut RenderOptions);
+    let tweaks: Vec<(&str, Tweak)> = vec![
+        ("none", |_, _| {}),
+        ("ascii", |_, o| o.charset = Charset::Ascii),
+        ("no color", |_, o| o.color = false),
+        ("no links", |_, o| o.hyperlinks = false),
+        ("no header", |_, o| o.show_header = false),
+        ("newlines", |_, o| o.trailing_newlines = 2),
+        ("untrimmed", |_, o| o.trim_trailing_spaces = false),
+        ("glyph width", |_, o| o.glyph_widths = vec![('-', 2)]),
+        ("max width", |_, o| o.max_width = Some(12)),
+        ("rtl", |_, o| o.right_to_left = true),
+        ("repeat", |_, o| o.repeat_header_every = Some(1)),
+        ("machine", |_, o| o.machine = Some(Machine::Spaces)),
+        ("grid", |_, o| o.style = TableStyle::Grid),
+        ("title", |t, _| t.set_title("People")),
+        ("max rows", |t, _| t.set_max_rows(1)),
+        ("row height", |t, _| t.set_max_row_height(1)),
+        ("legend", |t, _| {
+            t.set_legend(&[(ColorSpec::new(), "plain")])
+        }),
+        ("footer", |t, _| {
+            t.set_footer("Age", Aggregate::Sum).unwrap()
+        }),
+        ("total width", |t, _| {
+            t.set_total_width(20, Distribution::Even)
+        }),
+        ("locked", |t, _| t.lock_widths()),
+        ("vertical", |t, _| {
+            t.set_header_orientation(HeaderOrientation::Vertical)
+        }),
+        ("locale", |t, _| t.set_locale(Locale::DE)),
+        ("empty", |t, _| t.clear_rows()),
+        ("message", |t, _| {
+            t.clear_rows();
+            t.set_empty_policy(EmptyPolicy::ShowMessage("none".to_string()));
+        }),
+        ("index", |t, _| t.show_index(IndexFormat::Number)),
+        ("paging", |t, _| t.set_column_paging(true)),
+        ("breakpoint", |t, _| {
+            t.add_breakpoint(.., LayoutVariant::Records).unwrap()
+        }),
+        ("border color", |t, _| t.set_border_color(ColorSpec::new())),
+        ("row highlight", |t, _| {
+            let mut spec = ColorSpec::new();
+            spec.set_bold(true);
+            t.highlight_row(0, spec).unwrap();
+        }),
+        ("newline cell", |t, _| {
+            t.add_row(vec![
+                "Dee\nDee".to_string(),
+                "1".to_string(),
+                "X".to_string(),
+            ])
+        }),
+        ("wide cell", |t, _| {
+            t.add_row(vec!["Zoë 山".to_string(), "".to_string(), "".to_string()])
+        }),
+        ("fixed", |t, _| {
+            t.column_mut("Age")
+                .unwrap()
+                .format(Format::Fixed { decimals: 1 });
+        }),
+        ("wrap", |t, _| {
+            t.column_mut("City").unwrap().wrap(Wrap::Word);
+        }),
+        ("marker", |t, _| {
+            t.column_mut("City").unwrap().truncation_marker("~");
+        }),
+        ("middle", |t, _| {
+            t.column_mut(0)
+                .unwrap()
+                .vertical_alignment(VerticalAlignment::Middle);
+        }),
+        ("pinned", |t, _| {
+            t.column_mut(0).unwrap().pinned(true);
+        }),
+        ("priority", |t, _| {
+            t.column_mut(2).unwrap().priority(1);
+        }),
+        ("header alignment", |t, _| {
+            t.column_mut(0).unwrap().header_alignment(Alignment::Right);
+        }),
+        ("link", |t, _| {
+            t.column_mut(0).unwrap().link_template("https://x/{}");
+        }),
+        ("raw", |t, _| {
+            t.column_mut(0).unwrap().raw(true);
+        }),
+        ("unit", |t, _| {
+            t.column_mut("Age").unwrap().unit("y");
+        }),
+        ("description", |t, _| {
+            t.column_mut(0).unwrap().description("Who");
+        }),
+        ("signed", |t, _| {
+            t.column_mut("Age").unwrap().signed_colors(true);
+        }),
+    ];
+    for (name, tweak) in tweaks {
+        let mut table = create_test_table(TableStyle::Simple);
+        let mut options = table.render_options();
+        tweak(&mut table, &mut options);
+        let mut general = Vec::new();
+        table
+            .render_with(&mut PlainSink(&mut general), &options)
+            .unwrap();
+        if name == "none" {
+            assert!(table.fast_path(&options));
+        }
+        if table.fast_path(&options) {
+            let mut fast = Vec::new();
+            table.write_fast(&mut fast, &options).unwrap();
+            assert_eq!(
+                String::from_utf8(fast).unwrap(),
+                String::from_utf8(general).unwrap(),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/text.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::borrow::Cow;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{Alignment, Truncate};
+
+/// Terminal columns taken by one line of text, measured the way the
+/// renderer sizes cells: wide characters such as CJK count twice,
+/// combining marks not at all. ANSI escape sequences count as text; use
+/// `visible_width` for strings that carry colors or hyperlinks.
+pub fn display_width(s: &str) -> usize {
+    s.width()
+}
+
+/// Like `display_width`, not counting ANSI escape sequences, the way cells
+/// of raw columns are measured.
+pub fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            width += c.width().unwrap_or(0);
+            continue;
+        }
+        match chars.next() {
+            // CSI, such as colors: parameters up to a final byte in `@..=~`.
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC, such as hyperlinks: up to BEL or ESC `\`.
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        chars.next_if_eq(&'\\');
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    width
+}
+
+pub(crate) fn pad(s: &str, width: usize, alignment: Alignment) -> String {
+    pad_measured(s, display_width(s), width, alignment)
+}
+
+/// Pads `s`, already measured as `used` columns wide.
+pub(crate) fn pad_measured(s: &str, used: usize, width: usize, alignment: Alignment) -> String {
+    let fill = width.saturating_sub(used);
+    let (left, right) = match alignment {
+        Alignment::Left => (0, fill),
+        Alignment::Center => (fill / 2, fill - fill / 2),
+        Alignment::Right => (fill, 0),
+    };
+    let mut padded = String::with_capacity(s.len() + fill);
+    padded.extend(std::iter::repeat_n(' ', left));
+    padded.push_str(s);
+    padded.extend(std::iter::repeat_n(' ', right));
+    padded
+}
+
+pub(crate) const ELLIPSIS: &str = "…";
+
+pub(crate) fn truncate<'a>(
+    s: &'a str,
+    width: usize,
+    position: Truncate,
+    marker: &str,
+) -> Cow<'a, str> {
+    if display_width(s) <= width {
+        return Cow::Borrowed(s);
+    }
+    let marker_width = display_width(marker);
+    if marker_width > width {
+        return Cow::Owned(take_prefix(s, width).to_string());
+    }
+    let budget = width - marker_width;
+    Cow::Owned(match position {
+        Truncate::End => format!("{}{}", take_prefix(s, budget), marker),
+        Truncate::Start => format!("{}{}", marker, take_suffix(s, budget)),
+        Truncate::Middle => {
+            let tail = budget / 2;
+            let head = take_prefix(s, budget - tail);
+            format!("{}{}{}", head, marker, take_suffix(s, tail))
+        }
+    })
+}
+
+/// Replaces the end of `s` with `marker`, even if `s` already fits.
+pub(crate) fn with_marker(s: &str, width: usize, marker: &str) -> String {
+    let marker_width = display_width(marker);
+    if marker_width > width {
+        return take_prefix(s, width).to_string();
+    }
+    format!("{}{}", take_prefix(s, width - marker_width), marker)
+}
+
+fn take_prefix(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+fn take_suffix(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices().rev() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &s[i + c.len_utf8()..];
+        }
+    }
+    s
+}
+
+pub(crate) fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
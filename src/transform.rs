@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/transform.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io;
+
+use crate::text::display_width;
+use crate::{Alignment, ColumnRef, Table, TableError};
+
+impl Table {
+    /// Removes duplicate rows anywhere in the table, keeping the first occurrence.
+    pub fn dedup(&mut self) {
+        self.dedup_rows(None, None);
+    }
+
+    pub fn dedup_by_column(&mut self, column: impl Into<ColumnRef>) -> Result<(), TableError> {
+        let column = self.column_index(column)?;
+        self.dedup_rows(Some(column), None);
+        Ok(())
+    }
+
+    /// Like [`Table::dedup`], appending a column named `header` that holds how
+    /// many times each remaining row occurred.
+    pub fn dedup_with_count(&mut self, header: &str) {
+        self.dedup_rows(None, Some(header));
+    }
+
+    pub fn dedup_by_column_with_count(
+        &mut self,
+        column: impl Into<ColumnRef>,
+        header: &str,
+    ) -> Result<(), TableError> {
+        let column = self.column_index(column)?;
+        self.dedup_rows(Some(column), Some(header));
+        Ok(())
+    }
+
+    /// Returns a `(value, count)` table for `column`, most frequent first.
+    /// Values with equal counts keep the order in which they first appear.
+    pub fn value_counts(&self, column: impl Into<ColumnRef>) -> Result<Table, TableError> {
+        let column = self.column_index(column)?;
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for row in &self.rows {
+            let value = row[column].as_str();
+            match positions.get(value) {
+                Some(&index) => counts[index].1 += 1,
+                None => {
+                    positions.insert(value, counts.len());
+                    counts.push((value, 1));
+                }
+            }
+        }
+        counts.sort_by_key(|&(_, count)| Reverse(count));
+
+        let header = &self.columns[column].header;
+        let mut table = Table::new(self.style);
+        table.add_column(
+            header,
+            display_width(header),
+            self.columns[column].alignment,
+        );
+        table.add_column("count", display_width("count"), Alignment::Right);
+        for (value, count) in counts {
+            table.add_row(vec![value.to_string(), count.to_string()]);
+        }
+        Ok(table)
+    }
+
+    /// Row `index` as a two-column `Field`/`Value` table in this table's
+    /// style, with each value formatted as its column would show it. `None`
+    /// when there is no such row.
+    pub fn record(&self, index: usize) -> Option<Table> {
+        let row = self.rows.get(index)?;
+        let charset = self.render_options().charset;
+        let mut table = Table::new(self.style);
+        table.add_column("Field", 0, Alignment::Left);
+        table.add_column("Value", 0, Alignment::Left);
+        for (i, (column, cell)) in self.columns.iter().zip(row).enumerate() {
+            let value = column.display(cell, None, charset, self.locale_of(i));
+            table.add_row(vec![column.title().into_owned(), value.into_owned()]);
+        }
+        Some(table)
+    }
+
+    /// Prints [`Table::record`], e.g. to show the details of one item. A
+    /// missing row is a `RowOutOfRange` error.
+    pub fn print_record(&self, index: usize) -> io::Result<()> {
+        self.record(index)
+            .ok_or(TableError::RowOutOfRange(index))?
+            .print()
+    }
+
+    /// Splits the table into one sub-table per distinct value of `column`, in
+    /// order of first appearance. Each sub-table is titled "Header: value".
+    pub fn split_by(
+        &self,
+        column: impl Into<ColumnRef>,
+    ) -> Result<Vec<(String, Table)>, TableError> {
+        let column = self.column_index(column)?;
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        let mut groups: Vec<(String, Table)> = Vec::new();
+        for row in &self.rows {
+            let value = row[column].as_str();
+            let index = *positions.entry(value).or_insert_with(|| {
+                let mut table = self.empty_like();
+                table.set_title(&format!("{}: {}", self.columns[column].header, value));
+                groups.push((value.to_string(), table));
+                groups.len() - 1
+            });
+            groups[index].1.rows.push(row.clone());
+        }
+        Ok(groups)
+    }
+
+    fn dedup_rows(&mut self, column: Option<usize>, count_header: Option<&str>) {
+        let mut seen: HashMap<&[String], usize> = HashMap::new();
+        let mut kept: Vec<usize> = Vec::new();
+        let mut counts: Vec<usize> = Vec::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            let key = match column {
+                Some(column) => std::slice::from_ref(&row[column]),
+                None => row.as_slice(),
+            };
+            match seen.get(key) {
+                Some(&index) => counts[index] += 1,
+                None => {
+                    seen.insert(key, kept.len());
+                    kept.push(i);
+                    counts.push(1);
+                }
+            }
+        }
+        self.reorder_rows(&kept);
+        if let Some(header) = count_header {
+            self.add_column(header, display_width(header), Alignment::Right);
+            for (row, count) in self.rows.iter_mut().zip(counts) {
+                row.push(count.to_string());
+            }
+        }
+    }
+}
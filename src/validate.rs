@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/validate.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+#[cfg(feature = "csv")]
+use std::io;
+
+#[cfg(feature = "csv")]
+use crate::error::csv_error;
+use crate::{Column, ColumnRef, Locale, RowErrors, Table, TableError};
+
+#[derive(Clone, Debug)]
+pub enum Validator {
+    /// A number within `min..=max`.
+    Range {
+        min: f64,
+        max: f64,
+    },
+    OneOf(Vec<String>),
+    #[cfg(feature = "regex")]
+    Pattern(regex::Regex),
+}
+
+impl Validator {
+    /// Numbers are read with `locale`.
+    fn check(&self, cell: &str, locale: Locale) -> Result<(), String> {
+        match self {
+            Validator::Range { min, max } => match locale.parse(cell) {
+                Some(value) if (*min..=*max).contains(&value) => Ok(()),
+                _ => Err(format!("expected a number between {} and {}", min, max)),
+            },
+            Validator::OneOf(allowed) => {
+                if allowed.iter().any(|a| a == cell) {
+                    Ok(())
+                } else {
+                    Err(format!("expected one of {}", allowed.join(", ")))
+                }
+            }
+            #[cfg(feature = "regex")]
+            Validator::Pattern(pattern) => {
+                if pattern.is_match(cell) {
+                    Ok(())
+                } else {
+                    Err(format!("does not match /{}/", pattern.as_str()))
+                }
+            }
+        }
+    }
+}
+
+impl Column {
+    pub fn validator(&mut self, validator: Validator) -> &mut Self {
+        self.validator = Some(validator);
+        self
+    }
+}
+
+impl Table {
+    /// In strict mode a row with an invalid cell is rejected: `try_add_row`
+    /// and CSV imports return the error. Otherwise, and always with
+    /// `add_row`, the row is kept and the offending cells are recorded in
+    /// `violations()`.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn violations(&self) -> &[TableError] {
+        &self.violations
+    }
+
+    pub fn try_add_row(&mut self, row: Vec<String>) -> Result<(), TableError> {
+        let row = self.complete_row(row);
+        if row.len() != self.columns.len() {
+            return Err(TableError::RowLength {
+                row: self.rows.len(),
+                expected: self.columns.len(),
+                found: row.len(),
+            });
+        }
+        self.validate_row(&row)?;
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Replaces the row whose cell in `key` matches the new row's, or
+    /// appends the row when there is none, as suits a status table keyed
+    /// by host name or PID. Returns the row that was replaced.
+    pub fn upsert_by_key(
+        &mut self,
+        key: impl Into<ColumnRef>,
+        row: Vec<String>,
+    ) -> Result<Option<Vec<String>>, TableError> {
+        let key = self.column_index(key)?;
+        let row = self.complete_row(row);
+        let existing = self.rows.iter().position(|existing| {
+            row.get(key)
+                .is_some_and(|cell| existing.get(key) == Some(cell))
+        });
+        if row.len() != self.columns.len() {
+            return Err(TableError::RowLength {
+                row: existing.unwrap_or(self.rows.len()),
+                expected: self.columns.len(),
+                found: row.len(),
+            });
+        }
+        self.validate_row(&row)?;
+        match existing {
+            Some(index) => Ok(Some(self.replace_row(index, row))),
+            None => {
+                self.rows.push(row);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Adds every row that passes `try_add_row` and reports the rest together
+    /// with their position in `rows`.
+    pub fn try_add_rows<I>(&mut self, rows: I) -> Result<(), RowErrors>
+    where
+        I: IntoIterator<Item = Vec<String>>,
+    {
+        let errors: Vec<(usize, TableError)> = rows
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, row)| self.try_add_row(row).err().map(|e| (index, e)))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(RowErrors { errors })
+        }
+    }
+
+    /// Appends the records of a CSV file with a header row to this table,
+    /// validating each one against the column validators.
+    #[cfg(feature = "csv")]
+    pub fn append_csv(&mut self, path: &str) -> io::Result<()> {
+        let mut reader = csv::Reader::from_path(path)?;
+        for result in reader.records() {
+            let record = result.map_err(csv_error)?;
+            self.try_add_row(record.iter().map(|s| s.to_string()).collect())?;
+        }
+        Ok(())
+    }
+
+    /// The row as `try_add_row` would add it at position `index`, or the
+    /// error it would fail with. Nothing is added or recorded.
+    pub(crate) fn check_row(
+        &self,
+        row: Vec<String>,
+        index: usize,
+    ) -> Result<Vec<String>, TableError> {
+        let row = self.complete_row(row);
+        if row.len() != self.columns.len() {
+            return Err(TableError::RowLength {
+                row: index,
+                expected: self.columns.len(),
+                found: row.len(),
+            });
+        }
+        if self.strict {
+            if let Some(error) = self.invalid_cells(&row, index).next() {
+                return Err(error);
+            }
+        }
+        Ok(row)
+    }
+
+    pub(crate) fn validate_row(&mut self, row: &[String]) -> Result<(), TableError> {
+        if self.strict {
+            if let Some(error) = self.invalid_cells(row, self.rows.len()).next() {
+                return Err(error);
+            }
+        }
+        self.record_violations(row);
+        Ok(())
+    }
+
+    /// Records the invalid cells of `row`, about to be added at the end,
+    /// whether or not the table is strict.
+    pub(crate) fn record_violations(&mut self, row: &[String]) {
+        let errors: Vec<TableError> = self.invalid_cells(row, self.rows.len()).collect();
+        self.violations.extend(errors);
+    }
+
+    fn invalid_cells<'a>(
+        &'a self,
+        row: &'a [String],
+        index: usize,
+    ) -> impl Iterator<Item = TableError> + 'a {
+        row.iter()
+            .zip(&self.columns)
+            .enumerate()
+            .filter_map(move |(column, (cell, spec))| {
+                let reason = spec
+                    .validator
+                    .as_ref()?
+                    .check(cell, self.locale_of(column))
+                    .err()?;
+                Some(TableError::InvalidCell {
+                    row: index,
+                    column,
+                    value: cell.clone(),
+                    reason,
+                })
+            })
+    }
+}
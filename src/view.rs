@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/view.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::collections::HashMap;
+
+use crate::Table;
+
+impl Table {
+    pub fn head(&self, n: usize) -> Table {
+        let n = n.min(self.rows.len());
+        self.select_rows(0..n)
+    }
+
+    pub fn tail(&self, n: usize) -> Table {
+        let n = n.min(self.rows.len());
+        self.select_rows(self.rows.len() - n..self.rows.len())
+    }
+
+    /// Picks `n` rows at random, reproducibly for a given `seed`, keeping
+    /// their original order.
+    pub fn sample(&self, n: usize, seed: u64) -> Table {
+        let mut indices: Vec<usize> = (0..self.rows.len()).collect();
+        let n = n.min(indices.len());
+        let mut rng = SplitMix64(seed);
+        for i in 0..n {
+            let j = i + (rng.next() % (indices.len() - i) as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices.truncate(n);
+        indices.sort_unstable();
+        self.select_rows(indices)
+    }
+
+    pub(crate) fn select_rows(&self, indices: impl IntoIterator<Item = usize>) -> Table {
+        let indices: Vec<usize> = indices.into_iter().collect();
+        let position = positions(&indices);
+        let mut table = self.empty_like();
+        table.rows = indices.iter().map(|&i| self.rows[i].clone()).collect();
+        table.row_highlights = self
+            .row_highlights
+            .iter()
+            .filter_map(|(row, spec)| Some((*position.get(row)?, spec.clone())))
+            .collect();
+        table.cell_highlights = remap_cells(&self.cell_highlights, |row, column| {
+            Some((*position.get(&row)?, column))
+        });
+        table.cell_meta = remap_cells(&self.cell_meta, |row, column| {
+            Some((*position.get(&row)?, column))
+        });
+        table
+    }
+
+    /// Puts the rows in `order`, each entry the index of a current row;
+    /// rows left out are dropped. Highlights and HTML cell extras move along
+    /// with their rows.
+    pub(crate) fn reorder_rows(&mut self, order: &[usize]) {
+        let position = positions(order);
+        let mut rows: Vec<Option<Vec<String>>> = std::mem::take(&mut self.rows)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.rows = order
+            .iter()
+            .map(|&i| rows[i].take().expect("row listed twice"))
+            .collect();
+        self.row_highlights = self
+            .row_highlights
+            .drain()
+            .filter_map(|(row, spec)| Some((*position.get(&row)?, spec)))
+            .collect();
+        self.cell_highlights = remap_cells(&self.cell_highlights, |row, column| {
+            Some((*position.get(&row)?, column))
+        });
+        self.cell_meta = remap_cells(&self.cell_meta, |row, column| {
+            Some((*position.get(&row)?, column))
+        });
+        self.invalidate_stats();
+    }
+
+    /// Removes every row along with what was set on them.
+    pub(crate) fn clear_rows(&mut self) {
+        self.reorder_rows(&[]);
+    }
+
+    /// A copy holding only the given columns. Computed columns keep their
+    /// values but are no longer recomputed.
+    pub(crate) fn select_columns(&self, indices: &[usize]) -> Table {
+        let mut table = self.empty_like();
+        table.columns = indices
+            .iter()
+            .map(|&i| {
+                let mut column = self.columns[i].clone();
+                column.compute = None;
+                column
+            })
+            .collect();
+        table.rows = self
+            .rows
+            .iter()
+            .map(|row| indices.iter().map(|&i| row[i].clone()).collect())
+            .collect();
+        table.footers = self
+            .footers
+            .iter()
+            .filter_map(|(column, aggregate)| {
+                let position = indices.iter().position(|i| i == column)?;
+                Some((position, *aggregate))
+            })
+            .collect();
+        table.grand_total = self
+            .grand_total
+            .as_ref()
+            .map(|total| indices.iter().map(|&i| total[i].clone()).collect());
+        table.locked_widths = self
+            .locked_widths
+            .as_ref()
+            .filter(|widths| widths.len() == self.columns.len())
+            .map(|widths| indices.iter().map(|&i| widths[i]).collect());
+        let position = positions(indices);
+        table.row_highlights = self.row_highlights.clone();
+        table.cell_highlights = remap_cells(&self.cell_highlights, |row, column| {
+            Some((row, *position.get(&column)?))
+        });
+        table.cell_meta = remap_cells(&self.cell_meta, |row, column| {
+            Some((row, *position.get(&column)?))
+        });
+        table
+    }
+}
+
+/// Where each of the `order`ed indices ends up.
+fn positions(order: &[usize]) -> HashMap<usize, usize> {
+    order
+        .iter()
+        .enumerate()
+        .map(|(position, &index)| (index, position))
+        .collect()
+}
+
+/// Moves per-cell settings to their new `(row, column)`, dropping cells
+/// that `to` maps to `None`.
+pub(crate) fn remap_cells<T: Clone>(
+    cells: &HashMap<(usize, usize), T>,
+    to: impl Fn(usize, usize) -> Option<(usize, usize)>,
+) -> HashMap<(usize, usize), T> {
+    cells
+        .iter()
+        .filter_map(|(&(row, column), value)| Some((to(row, column)?, value.clone())))
+        .collect()
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
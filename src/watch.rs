@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/watch.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+use terminal_size::{terminal_size, Width};
+
+use crate::{ColorSpec, RenderOptions, Table, TableError};
+
+/// How often an idle watcher checks whether the terminal was resized.
+const RESIZE_POLL: Duration = Duration::from_millis(250);
+
+type RejectFn = Box<dyn FnMut(&TableError) + Send>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RowUpdate {
+    Append(Vec<String>),
+    /// Replaces every row of the table.
+    Replace(Vec<Vec<String>>),
+    Clear,
+}
+
+pub struct TableWatcher {
+    table: Table,
+    updates: Receiver<RowUpdate>,
+    debounce: Duration,
+    changes: Option<ChangeMarks>,
+    on_rejected: Option<RejectFn>,
+}
+
+/// Cells highlighted because their value changed between frames.
+struct ChangeMarks {
+    spec: ColorSpec,
+    frames: usize,
+    previous: Vec<Vec<String>>,
+    /// Frames left for each marked cell, and the highlight the mark covers.
+    marked: HashMap<(usize, usize), (usize, Option<ColorSpec>)>,
+}
+
+impl ChangeMarks {
+    /// Ages the marks of the last frame and marks what changed since.
+    fn advance(&mut self, table: &mut Table) {
+        self.marked.retain(|cell, (left, covered)| {
+            *left -= 1;
+            if *left > 0 {
+                return true;
+            }
+            match covered.take() {
+                Some(spec) => table.cell_highlights.insert(*cell, spec),
+                None => table.cell_highlights.remove(cell),
+            };
+            false
+        });
+        for (row, (cells, before)) in table.rows.iter().zip(&self.previous).enumerate() {
+            for (column, _) in cells
+                .iter()
+                .zip(before)
+                .enumerate()
+                .filter(|(_, (now, before))| now != before)
+            {
+                let cell = (row, column);
+                let covered = match self.marked.remove(&cell) {
+                    Some((_, covered)) => covered,
+                    None => table.cell_highlights.get(&cell).cloned(),
+                };
+                table.cell_highlights.insert(cell, self.spec.clone());
+                self.marked.insert(cell, (self.frames, covered));
+            }
+        }
+        self.previous = table.rows.clone();
+    }
+}
+
+impl TableWatcher {
+    pub fn new(table: Table) -> (Self, Sender<RowUpdate>) {
+        let (sender, updates) = mpsc::channel();
+        let watcher = TableWatcher {
+            table,
+            updates,
+            debounce: Duration::from_millis(100),
+            changes: None,
+            on_rejected: None,
+        };
+        (watcher, sender)
+    }
+
+    /// How long to wait for further updates before re-rendering.
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
+
+    /// Highlights cells whose value changed since the previous frame with
+    /// `spec` for the next `frames` frames, so changes stand out on a
+    /// dashboard.
+    pub fn mark_changes(&mut self, spec: ColorSpec, frames: usize) {
+        self.changes = (frames > 0).then(|| ChangeMarks {
+            spec,
+            frames,
+            previous: self.table.rows.clone(),
+            marked: HashMap::new(),
+        });
+    }
+
+    /// Called for every row the watcher rejects. A rejected row is skipped;
+    /// a `Replace` with a rejected row is skipped as a whole, leaving the
+    /// table as it was.
+    pub fn on_rejected<F>(&mut self, report: F)
+    where
+        F: FnMut(&TableError) + Send + 'static,
+    {
+        self.on_rejected = Some(Box::new(report));
+    }
+
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    pub fn into_table(self) -> Table {
+        self.table
+    }
+
+    /// Clears the screen and reprints the table, fitted to the terminal,
+    /// after every burst of updates and whenever the terminal is resized,
+    /// until all senders are dropped.
+    pub fn run<W: Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.run_with(|table| {
+            writer.write_all(b"\x1b[2J\x1b[H")?;
+            table.print_ansi(writer, &frame_options(table))
+        })
+    }
+
+    /// Like [`TableWatcher::run`], but hands the table to `render` instead of
+    /// printing it.
+    pub fn run_with<F>(&mut self, mut render: F) -> io::Result<()>
+    where
+        F: FnMut(&Table) -> io::Result<()>,
+    {
+        let mut size = terminal_size();
+        loop {
+            let update = match self.updates.recv_timeout(RESIZE_POLL) {
+                Ok(update) => update,
+                Err(RecvTimeoutError::Timeout) => {
+                    if terminal_size() != size {
+                        size = terminal_size();
+                        self.frame(&mut render)?;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            };
+            self.apply(update);
+            loop {
+                match self.updates.recv_timeout(self.debounce) {
+                    Ok(update) => self.apply(update),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return self.frame(&mut render),
+                }
+            }
+            size = terminal_size();
+            self.frame(&mut render)?;
+        }
+    }
+
+    fn frame<F>(&mut self, render: &mut F) -> io::Result<()>
+    where
+        F: FnMut(&Table) -> io::Result<()>,
+    {
+        if let Some(changes) = &mut self.changes {
+            changes.advance(&mut self.table);
+        }
+        render(&self.table)
+    }
+
+    fn apply(&mut self, update: RowUpdate) {
+        match update {
+            RowUpdate::Append(row) => {
+                if let Err(error) = self.table.try_add_row(row) {
+                    self.reject(&error);
+                }
+            }
+            RowUpdate::Replace(rows) => {
+                let mut checked = Vec::with_capacity(rows.len());
+                let mut errors = Vec::new();
+                for (index, row) in rows.into_iter().enumerate() {
+                    match self.table.check_row(row, index) {
+                        Ok(row) => checked.push(row),
+                        Err(error) => errors.push(error),
+                    }
+                }
+                if !errors.is_empty() {
+                    errors.iter().for_each(|error| self.reject(error));
+                    return;
+                }
+                self.clear();
+                for row in checked {
+                    if let Err(error) = self.table.try_add_row(row) {
+                        self.reject(&error);
+                    }
+                }
+            }
+            RowUpdate::Clear => self.clear(),
+        }
+    }
+
+    fn reject(&mut self, error: &TableError) {
+        if let Some(report) = &mut self.on_rejected {
+            report(error);
+        }
+    }
+
+    /// Drops every row and the highlights set on them, keeping the change
+    /// marks of the current frame on screen.
+    fn clear(&mut self) {
+        self.table.clear_rows();
+        if let Some(changes) = &mut self.changes {
+            for (cell, (_, covered)) in &mut changes.marked {
+                *covered = None;
+                self.table
+                    .cell_highlights
+                    .insert(*cell, changes.spec.clone());
+            }
+        }
+    }
+}
+
+/// The table's options, narrowed to the terminal's current width.
+fn frame_options(table: &Table) -> RenderOptions {
+    let mut options = table.render_options();
+    options.max_width = terminal_size().map(|(Width(width), _)| usize::from(width));
+    options
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MIT
+// Project: tabprinter
+// File: src/wrap.rs
+// Author: Volker Schwaberow <volker@schwaberow.de>
+// Copyright (c) 2024 Volker Schwaberow
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::text::{display_width, truncate};
+use crate::{Charset, Column};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Wrap {
+    /// Keep each line on one row, truncating it when the column is too narrow.
+    #[default]
+    Off,
+    /// Break at whitespace; words wider than the column are split.
+    Word,
+    /// Break at the column width regardless of word boundaries.
+    Anywhere,
+    /// Break at whitespace, but split words of at least `threshold` columns
+    /// to fill the current line instead of moving them to the next one.
+    Hybrid { threshold: usize },
+}
+
+/// Where a line that does not fit is cut when wrapping is off.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Truncate {
+    /// Keep the end, e.g. for paths and IDs that differ in their suffix.
+    Start,
+    Middle,
+    #[default]
+    End,
+}
+
+#[cfg(test)]
+pub(crate) fn wrap(text: &str, width: usize, mode: Wrap) -> Vec<String> {
+    text.split('\n')
+        .flat_map(|line| wrap_line(line, width, mode, Truncate::End, crate::text::ELLIPSIS))
+        .collect()
+}
+
+pub(crate) fn wrap_cell(
+    text: &str,
+    width: usize,
+    column: &Column,
+    charset: Charset,
+) -> Vec<String> {
+    let marker = charset.marker(&column.marker);
+    text.split('\n')
+        .flat_map(|line| wrap_line(line, width, column.wrap, column.truncate, marker))
+        .collect()
+}
+
+fn wrap_line(
+    line: &str,
+    width: usize,
+    mode: Wrap,
+    position: Truncate,
+    marker: &str,
+) -> Vec<String> {
+    match mode {
+        Wrap::Off => vec![truncate(line, width, position, marker).into_owned()],
+        _ if width == 0 => vec![String::new()],
+        Wrap::Anywhere => break_anywhere(line, width),
+        Wrap::Word => break_words(line, width, None),
+        Wrap::Hybrid { threshold } => break_words(line, width, Some(threshold)),
+    }
+}
+
+fn split_at_width(s: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width && i > 0 {
+            return s.split_at(i);
+        }
+        used += w;
+    }
+    (s, "")
+}
+
+fn break_anywhere(line: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let (head, tail) = split_at_width(rest, width);
+        lines.push(head.to_string());
+        rest = tail;
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+fn break_words(line: &str, width: usize, threshold: Option<usize>) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        let gap = usize::from(!current.is_empty());
+        let used = display_width(&current);
+        if used + gap + display_width(word) <= width {
+            if gap == 1 {
+                current.push(' ');
+            }
+            current.push_str(word);
+            continue;
+        }
+
+        let mut rest = word;
+        let splits_early = threshold.is_some_and(|t| display_width(word) >= t);
+        if splits_early && used + gap < width {
+            let (head, tail) = split_at_width(word, width - used - gap);
+            if gap == 1 {
+                current.push(' ');
+            }
+            current.push_str(head);
+            rest = tail;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        while display_width(rest) > width {
+            let (head, tail) = split_at_width(rest, width);
+            lines.push(head.to_string());
+            rest = tail;
+        }
+        current.push_str(rest);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}